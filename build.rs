@@ -1,10 +1,23 @@
 fn main() {
-    println!("cargo:rerun-if-changed=.env");
-    dotenvy::dotenv().ok();
-    
-    if let Ok(url) = std::env::var("FIREBASE_URL") {
-        println!("cargo:rustc-env=FIREBASE_URL={}", url);
-    } else {
-        println!("cargo:warning=FIREBASE_URL not found in .env file!");
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo::rustc-check-cfg=cfg(audio_output)");
+    println!("cargo::rustc-check-cfg=cfg(gamepad_input)");
+
+    // Device output is desktop only. cpal needs the ALSA development headers on
+    // Linux, so desktop Linux builds only get it with the `linux-audio` feature.
+    let target_os = std::env::var("CARGO_CFG_TARGET_OS").unwrap_or_default();
+    if target_os == "windows"
+        || target_os == "macos"
+        || (target_os == "linux" && std::env::var("CARGO_FEATURE_LINUX_AUDIO").is_ok())
+    {
+        println!("cargo:rustc-cfg=audio_output");
     }
-}
\ No newline at end of file
+
+    // Controller support is desktop only, and on Linux gilrs needs libudev.
+    if target_os == "windows"
+        || target_os == "macos"
+        || (target_os == "linux" && std::env::var("CARGO_FEATURE_LINUX_GAMEPAD").is_ok())
+    {
+        println!("cargo:rustc-cfg=gamepad_input");
+    }
+}
@@ -0,0 +1,90 @@
+use std::collections::HashMap;
+use crate::resources::AudioSettings;
+use super::synth::{render_sfx, MusicSequencer, Sfx};
+
+struct Voice {
+    sfx: Sfx,
+    position: usize,
+}
+
+/// Mixes sound effects and music into a mono stream. It has no device dependency,
+/// so the output stream and offline renders share the exact same code path.
+pub struct Mixer {
+    sample_rate: u32,
+    settings: AudioSettings,
+    cache: HashMap<Sfx, Vec<f32>>,
+    voices: Vec<Voice>,
+    music: MusicSequencer,
+    music_playing: bool,
+}
+
+impl Mixer {
+    pub fn new(sample_rate: u32) -> Self {
+        Self {
+            sample_rate,
+            settings: AudioSettings::default(),
+            cache: HashMap::new(),
+            voices: Vec::new(),
+            music: MusicSequencer::new(sample_rate),
+            music_playing: false,
+        }
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    pub fn set_sample_rate(&mut self, sample_rate: u32) {
+        if sample_rate != self.sample_rate {
+            self.sample_rate = sample_rate;
+            self.cache.clear();
+            self.voices.clear();
+            self.music.set_sample_rate(sample_rate);
+        }
+    }
+
+    pub fn apply_settings(&mut self, settings: &AudioSettings) {
+        self.settings = settings.clone();
+    }
+
+    pub fn play(&mut self, sfx: Sfx) {
+        if self.settings.muted {
+            return;
+        }
+
+        let sample_rate = self.sample_rate;
+        self.cache.entry(sfx).or_insert_with(|| render_sfx(sfx, sample_rate));
+        self.voices.push(Voice { sfx, position: 0 });
+    }
+
+    pub fn set_music(&mut self, playing: bool, step_duration: f32) {
+        self.music_playing = playing;
+        self.music.set_step_duration(step_duration);
+    }
+
+    pub fn fill(&mut self, out: &mut [f32]) {
+        let music_on = self.music_playing && self.settings.music_enabled && !self.settings.muted;
+
+        for sample in out.iter_mut() {
+            let mut mixed = 0.0;
+
+            for voice in &mut self.voices {
+                if let Some(value) = self.cache.get(&voice.sfx).and_then(|s| s.get(voice.position)) {
+                    mixed += value * self.settings.sfx_volume;
+                }
+                voice.position += 1;
+            }
+
+            if music_on {
+                mixed += self.music.next_sample() * self.settings.music_volume;
+            }
+
+            *sample = if self.settings.muted { 0.0 } else { mixed.clamp(-1.0, 1.0) };
+        }
+
+        let cache = &self.cache;
+        self.voices.retain(|voice| {
+            cache.get(&voice.sfx).is_some_and(|s| voice.position < s.len())
+        });
+    }
+}
@@ -0,0 +1,120 @@
+pub mod mixer;
+#[cfg(audio_output)]
+mod output;
+pub mod synth;
+pub mod wav;
+
+use std::sync::{Arc, Mutex};
+use crate::events::{GameEvent, GameEventListener};
+use crate::resources::{AudioSettings, FoodKind, Game};
+use mixer::Mixer;
+use synth::{MusicSequencer, Sfx};
+
+pub const DEFAULT_SAMPLE_RATE: u32 = 44_100;
+
+pub struct AudioSystem {
+    mixer: Arc<Mutex<Mixer>>,
+    #[cfg(audio_output)]
+    _output: Option<output::AudioOutput>,
+}
+
+impl AudioSystem {
+    /// Opens the default output device when one is available. Without a device
+    /// the system still accepts every call and simply produces no sound.
+    pub fn new(settings: &AudioSettings) -> Self {
+        let mut mixer = Mixer::new(DEFAULT_SAMPLE_RATE);
+        mixer.apply_settings(settings);
+        let mixer = Arc::new(Mutex::new(mixer));
+
+        Self {
+            #[cfg(audio_output)]
+            _output: output::AudioOutput::start(mixer.clone()),
+            mixer,
+        }
+    }
+
+    pub fn apply_settings(&mut self, settings: &AudioSettings) {
+        if let Ok(mut mixer) = self.mixer.lock() {
+            mixer.apply_settings(settings);
+        }
+    }
+
+    pub fn play(&mut self, sfx: Sfx) {
+        if let Ok(mut mixer) = self.mixer.lock() {
+            mixer.play(sfx);
+        }
+    }
+
+    pub fn set_music(&mut self, playing: bool, step_duration: f32) {
+        if let Ok(mut mixer) = self.mixer.lock() {
+            mixer.set_music(playing, step_duration);
+        }
+    }
+}
+
+impl GameEventListener for AudioSystem {
+    fn on_game_event(&mut self, _game: &Game, event: &GameEvent) {
+        let sfx = match event {
+            GameEvent::AteFood { kind: FoodKind::Apple, .. } => Sfx::Eat,
+            GameEvent::Turned { .. } => Sfx::Turn,
+            GameEvent::Died { .. } => Sfx::Die,
+        };
+        self.play(sfx);
+    }
+}
+
+/// Renders a single effect to a WAV file in memory, without touching any device.
+pub fn render_sfx_wav(sfx: Sfx, sample_rate: u32) -> Vec<u8> {
+    wav::encode_wav(&synth::render_sfx(sfx, sample_rate), sample_rate)
+}
+
+/// Renders `seconds` of the music loop at the tempo used for `step_duration`.
+pub fn render_music_wav(seconds: f32, step_duration: f32, sample_rate: u32) -> Vec<u8> {
+    let mut sequencer = MusicSequencer::new(sample_rate);
+    sequencer.set_step_duration(step_duration);
+
+    let count = (seconds * sample_rate as f32) as usize;
+    let samples: Vec<f32> = (0..count).map(|_| sequencer.next_sample()).collect();
+
+    wav::encode_wav(&samples, sample_rate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RATE: u32 = 8_000;
+
+    fn read_u32(bytes: &[u8], at: usize) -> u32 {
+        u32::from_le_bytes(bytes[at..at + 4].try_into().unwrap())
+    }
+
+    /// Checks the header fields and returns the decoded samples.
+    fn decode(bytes: &[u8]) -> Vec<i16> {
+        assert_eq!(&bytes[0..4], b"RIFF");
+        assert_eq!(read_u32(bytes, 4) as usize, bytes.len() - 8);
+        assert_eq!(&bytes[8..12], b"WAVE");
+        assert_eq!(&bytes[12..16], b"fmt ");
+        assert_eq!(read_u32(bytes, 24), RATE);
+        assert_eq!(&bytes[36..40], b"data");
+        assert_eq!(read_u32(bytes, 40) as usize, bytes.len() - 44);
+
+        bytes[44..].chunks_exact(2).map(|pair| i16::from_le_bytes([pair[0], pair[1]])).collect()
+    }
+
+    #[test]
+    fn sfx_wav_has_header_and_sound() {
+        let samples = decode(&render_sfx_wav(Sfx::Eat, RATE));
+
+        assert_eq!(samples.len(), synth::render_sfx(Sfx::Eat, RATE).len());
+        assert!(samples.iter().any(|&s| s.unsigned_abs() > 1000));
+    }
+
+    #[test]
+    fn music_wav_has_requested_length_and_sound() {
+        let samples = decode(&render_music_wav(0.5, crate::constants::TICK_INTERVAL, RATE));
+
+        assert_eq!(samples.len(), (RATE / 2) as usize);
+        assert!(samples.iter().any(|&s| s.unsigned_abs() > 1000));
+    }
+}
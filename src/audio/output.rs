@@ -0,0 +1,63 @@
+use std::sync::{Arc, Mutex};
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use super::mixer::Mixer;
+
+/// Keeps the device stream alive. Dropping it stops playback.
+pub struct AudioOutput {
+    _stream: cpal::Stream,
+}
+
+impl AudioOutput {
+    pub fn start(mixer: Arc<Mutex<Mixer>>) -> Option<Self> {
+        let device = cpal::default_host().default_output_device()?;
+        let supported = device.default_output_config().ok()?;
+        let sample_format = supported.sample_format();
+        let config: cpal::StreamConfig = supported.into();
+
+        if let Ok(mut mixer) = mixer.lock() {
+            mixer.set_sample_rate(config.sample_rate.0);
+        }
+
+        let stream = match sample_format {
+            cpal::SampleFormat::F32 => build_stream::<f32>(&device, &config, mixer)?,
+            cpal::SampleFormat::I16 => build_stream::<i16>(&device, &config, mixer)?,
+            cpal::SampleFormat::U16 => build_stream::<u16>(&device, &config, mixer)?,
+            _ => return None,
+        };
+        stream.play().ok()?;
+
+        Some(Self { _stream: stream })
+    }
+}
+
+fn build_stream<T>(
+    device: &cpal::Device,
+    config: &cpal::StreamConfig,
+    mixer: Arc<Mutex<Mixer>>,
+) -> Option<cpal::Stream>
+where
+    T: cpal::SizedSample + cpal::FromSample<f32>,
+{
+    let channels = config.channels as usize;
+    let mut mono = Vec::new();
+
+    device
+        .build_output_stream(
+            config,
+            move |data: &mut [T], _: &cpal::OutputCallbackInfo| {
+                mono.resize(data.len() / channels, 0.0);
+                match mixer.lock() {
+                    Ok(mut mixer) => mixer.fill(&mut mono),
+                    Err(_) => mono.fill(0.0),
+                }
+                for (frame, &sample) in data.chunks_mut(channels).zip(&mono) {
+                    for channel in frame {
+                        *channel = T::from_sample(sample);
+                    }
+                }
+            },
+            |_| {},
+            None,
+        )
+        .ok()
+}
@@ -0,0 +1,161 @@
+#[derive(Clone, Copy, PartialEq)]
+pub enum Waveform {
+    Square,
+    Triangle,
+    Saw,
+    Noise,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Sfx {
+    Eat,
+    Turn,
+    Die,
+}
+
+#[derive(Clone, Copy)]
+struct Tone {
+    wave: Waveform,
+    start_freq: f32,
+    end_freq: f32,
+    duration: f32,
+    volume: f32,
+}
+
+const ATTACK_SECONDS: f32 = 0.005;
+
+/// Deterministic noise so offline renders are reproducible.
+struct NoiseSource(u32);
+
+impl NoiseSource {
+    fn next(&mut self) -> f32 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 17;
+        self.0 ^= self.0 << 5;
+        (self.0 as f32 / u32::MAX as f32) * 2.0 - 1.0
+    }
+}
+
+fn oscillator(wave: Waveform, phase: f32, noise: &mut NoiseSource) -> f32 {
+    match wave {
+        Waveform::Square => if phase < 0.5 { 1.0 } else { -1.0 },
+        Waveform::Triangle => 1.0 - 4.0 * (phase - 0.5).abs(),
+        Waveform::Saw => 2.0 * phase - 1.0,
+        Waveform::Noise => noise.next(),
+    }
+}
+
+fn render_tones(tones: &[Tone], sample_rate: u32) -> Vec<f32> {
+    let rate = sample_rate as f32;
+    let mut noise = NoiseSource(0x1234_5678);
+    let mut samples = Vec::new();
+
+    for tone in tones {
+        let count = (tone.duration * rate) as usize;
+        let mut phase = 0.0_f32;
+
+        for i in 0..count {
+            let t = i as f32 / rate;
+            let progress = i as f32 / count as f32;
+            let freq = tone.start_freq + (tone.end_freq - tone.start_freq) * progress;
+
+            let attack = (t / ATTACK_SECONDS).min(1.0);
+            let release = 1.0 - progress;
+            let envelope = attack * release;
+
+            samples.push(oscillator(tone.wave, phase, &mut noise) * envelope * tone.volume);
+
+            phase = (phase + freq / rate).fract();
+        }
+    }
+
+    samples
+}
+
+pub fn render_sfx(sfx: Sfx, sample_rate: u32) -> Vec<f32> {
+    let tone = |wave, start_freq, end_freq, duration, volume| Tone {
+        wave,
+        start_freq,
+        end_freq,
+        duration,
+        volume,
+    };
+
+    let tones = match sfx {
+        Sfx::Eat => vec![tone(Waveform::Square, 660.0, 990.0, 0.08, 0.35)],
+        Sfx::Turn => vec![tone(Waveform::Triangle, 320.0, 280.0, 0.03, 0.25)],
+        Sfx::Die => vec![
+            tone(Waveform::Saw, 400.0, 80.0, 0.35, 0.4),
+            tone(Waveform::Noise, 0.0, 0.0, 0.2, 0.3),
+        ],
+    };
+
+    render_tones(&tones, sample_rate)
+}
+
+const LEAD_PATTERN: [u8; 16] = [
+    72, 0, 76, 0, 79, 0, 76, 74,
+    72, 0, 69, 0, 71, 72, 74, 0,
+];
+const BASS_PATTERN: [u8; 8] = [48, 48, 45, 45, 41, 41, 43, 43];
+
+fn midi_to_freq(note: u8) -> f32 {
+    440.0 * 2.0_f32.powf((note as f32 - 69.0) / 12.0)
+}
+
+/// Endless two-voice chiptune loop. One pattern step lasts one game tick, so the
+/// music keeps time with the snake's movement.
+pub struct MusicSequencer {
+    sample_rate: f32,
+    step_duration: f32,
+    step: usize,
+    step_position: f32,
+    lead_phase: f32,
+    bass_phase: f32,
+}
+
+impl MusicSequencer {
+    pub fn new(sample_rate: u32) -> Self {
+        Self {
+            sample_rate: sample_rate as f32,
+            step_duration: 0.15,
+            step: 0,
+            step_position: 0.0,
+            lead_phase: 0.0,
+            bass_phase: 0.0,
+        }
+    }
+
+    pub fn set_step_duration(&mut self, seconds: f32) {
+        self.step_duration = seconds.max(0.02);
+    }
+
+    pub fn set_sample_rate(&mut self, sample_rate: u32) {
+        self.sample_rate = sample_rate as f32;
+    }
+
+    pub fn next_sample(&mut self) -> f32 {
+        let lead_note = LEAD_PATTERN[self.step % LEAD_PATTERN.len()];
+        let bass_note = BASS_PATTERN[(self.step / 2) % BASS_PATTERN.len()];
+        let decay = (1.0 - self.step_position / self.step_duration).max(0.0);
+
+        let mut sample = 0.0;
+        if lead_note != 0 {
+            let lead = if self.lead_phase < 0.25 { 1.0 } else { -1.0 };
+            sample += lead * decay * decay * 0.3;
+            self.lead_phase = (self.lead_phase + midi_to_freq(lead_note) / self.sample_rate).fract();
+        }
+
+        let bass = 1.0 - 4.0 * (self.bass_phase - 0.5).abs();
+        sample += bass * 0.25;
+        self.bass_phase = (self.bass_phase + midi_to_freq(bass_note) / self.sample_rate).fract();
+
+        self.step_position += 1.0 / self.sample_rate;
+        if self.step_position >= self.step_duration {
+            self.step_position -= self.step_duration;
+            self.step = (self.step + 1) % LEAD_PATTERN.len();
+        }
+
+        sample
+    }
+}
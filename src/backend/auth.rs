@@ -0,0 +1,222 @@
+use std::path::PathBuf;
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+use crate::period;
+use super::{BackendError, BoxFuture};
+
+const FIREBASE_IDENTITY_URL: &str = "https://identitytoolkit.googleapis.com";
+const FIREBASE_TOKEN_URL: &str = "https://securetoken.googleapis.com";
+/// Tokens this close to expiring are refreshed before use rather than risking a
+/// write being rejected halfway through.
+const REFRESH_MARGIN: u64 = 60;
+
+/// What an install signs its writes with. `uid` is the account the identity
+/// provider made for this install, which is not the same as the player's user id.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Credentials {
+    pub uid: String,
+    pub id_token: String,
+    pub refresh_token: String,
+    /// Unix seconds.
+    pub expires_at: u64,
+}
+
+impl Credentials {
+    fn is_fresh(&self, now: u64) -> bool {
+        self.expires_at > now + REFRESH_MARGIN
+    }
+}
+
+/// Hands out anonymous accounts and short-lived ID tokens for them.
+pub trait IdentityProvider: Send + Sync {
+    /// Creates a new anonymous account.
+    fn sign_in(&self) -> BoxFuture<'_, Result<Credentials, BackendError>>;
+
+    /// A new ID token for the same account.
+    fn refresh<'a>(&'a self, credentials: &'a Credentials) -> BoxFuture<'a, Result<Credentials, BackendError>>;
+}
+
+/// Firebase Authentication's REST API, or anything speaking the same protocol
+/// such as [`StubIdentityServer`](super::StubIdentityServer).
+pub struct FirebaseIdentity {
+    identity_url: String,
+    token_url: String,
+    api_key: String,
+    client: reqwest::Client,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SignUpResponse {
+    local_id: String,
+    id_token: String,
+    refresh_token: String,
+    expires_in: String,
+}
+
+#[derive(Deserialize)]
+struct RefreshResponse {
+    user_id: String,
+    id_token: String,
+    refresh_token: String,
+    expires_in: String,
+}
+
+#[derive(Deserialize)]
+struct ErrorResponse {
+    error: ErrorDetail,
+}
+
+#[derive(Deserialize)]
+struct ErrorDetail {
+    message: String,
+}
+
+impl FirebaseIdentity {
+    pub fn new(api_key: &str) -> Self {
+        Self::with_endpoints(FIREBASE_IDENTITY_URL, FIREBASE_TOKEN_URL, api_key)
+    }
+
+    /// Points the sign-up and token requests somewhere else, e.g. a local stand-in.
+    pub fn with_endpoints(identity_url: &str, token_url: &str, api_key: &str) -> Self {
+        Self {
+            identity_url: identity_url.trim_end_matches('/').to_string(),
+            token_url: token_url.trim_end_matches('/').to_string(),
+            api_key: api_key.to_string(),
+            client: reqwest::Client::new(),
+        }
+    }
+
+    async fn sign_up(&self) -> Result<Credentials, BackendError> {
+        let url = format!("{}/v1/accounts:signUp", self.identity_url);
+        let response = self
+            .client
+            .post(&url)
+            .query(&[("key", &self.api_key)])
+            .json(&serde_json::json!({ "returnSecureToken": true }))
+            .send()
+            .await?;
+        let body: SignUpResponse = read_response(response).await?;
+
+        Ok(Credentials {
+            uid: body.local_id,
+            id_token: body.id_token,
+            refresh_token: body.refresh_token,
+            expires_at: expires_at(&body.expires_in)?,
+        })
+    }
+
+    async fn exchange(&self, refresh_token: &str) -> Result<Credentials, BackendError> {
+        let url = format!("{}/v1/token", self.token_url);
+        let response = self
+            .client
+            .post(&url)
+            .query(&[("key", &self.api_key)])
+            .form(&[("grant_type", "refresh_token"), ("refresh_token", refresh_token)])
+            .send()
+            .await?;
+        let body: RefreshResponse = read_response(response).await?;
+
+        Ok(Credentials {
+            uid: body.user_id,
+            id_token: body.id_token,
+            refresh_token: body.refresh_token,
+            expires_at: expires_at(&body.expires_in)?,
+        })
+    }
+}
+
+impl IdentityProvider for FirebaseIdentity {
+    fn sign_in(&self) -> BoxFuture<'_, Result<Credentials, BackendError>> {
+        Box::pin(self.sign_up())
+    }
+
+    fn refresh<'a>(&'a self, credentials: &'a Credentials) -> BoxFuture<'a, Result<Credentials, BackendError>> {
+        Box::pin(self.exchange(&credentials.refresh_token))
+    }
+}
+
+/// Server errors stay as HTTP errors so they're retried; anything else means the
+/// request itself was refused, and the provider's reason is kept.
+async fn read_response<T: serde::de::DeserializeOwned>(response: reqwest::Response) -> Result<T, BackendError> {
+    let status = response.status();
+    if status.is_server_error() {
+        return Err(BackendError::Http(status.as_u16()));
+    }
+
+    let text = response.text().await?;
+    if !status.is_success() {
+        let reason = serde_json::from_str::<ErrorResponse>(&text)
+            .map(|e| e.error.message)
+            .unwrap_or_else(|_| format!("HTTP {}", status.as_u16()));
+        return Err(BackendError::SignIn(reason));
+    }
+
+    Ok(serde_json::from_str(&text)?)
+}
+
+fn expires_at(expires_in: &str) -> Result<u64, BackendError> {
+    let seconds: u64 = expires_in
+        .parse()
+        .map_err(|_| BackendError::Parse(format!("bad token lifetime \"{}\"", expires_in)))?;
+    Ok(period::now() + seconds)
+}
+
+/// This install's sign-in. Credentials are kept on disk so the install keeps the
+/// same account, and with it ownership of its leaderboard entries, across restarts.
+pub struct Session {
+    provider: Box<dyn IdentityProvider>,
+    path: Option<PathBuf>,
+    credentials: Mutex<Option<Credentials>>,
+}
+
+impl Session {
+    pub fn new(provider: Box<dyn IdentityProvider>, path: Option<PathBuf>) -> Self {
+        let credentials = path
+            .as_ref()
+            .and_then(|p| std::fs::read_to_string(p).ok())
+            .and_then(|json| serde_json::from_str(&json).ok());
+
+        Self {
+            provider,
+            path,
+            credentials: Mutex::new(credentials),
+        }
+    }
+
+    /// Credentials with a usable ID token, signing in or refreshing first if needed.
+    /// Only signs up when the install has no account yet.
+    pub async fn credentials(&self) -> Result<Credentials, BackendError> {
+        let mut current = self.credentials.lock().await;
+
+        let credentials = match current.as_ref() {
+            Some(credentials) if credentials.is_fresh(period::now()) => return Ok(credentials.clone()),
+            // A refused refresh is passed on rather than signing up again: a new
+            // account wouldn't own this install's entries, so every write after it
+            // would be rejected. The old credentials are kept for the next try.
+            Some(credentials) => self.provider.refresh(credentials).await?,
+            None => self.provider.sign_in().await?,
+        };
+
+        self.save(&credentials);
+        *current = Some(credentials.clone());
+        Ok(credentials)
+    }
+
+    /// Marks the token as used up after the server rejected it, so the next call
+    /// to [`credentials`](Self::credentials) refreshes. Does nothing if another
+    /// write has already refreshed it in the meantime.
+    pub async fn expire(&self, rejected: &Credentials) {
+        if let Some(current) = self.credentials.lock().await.as_mut() {
+            if current.id_token == rejected.id_token {
+                current.expires_at = 0;
+            }
+        }
+    }
+
+    fn save(&self, credentials: &Credentials) {
+        if let (Some(path), Ok(json)) = (&self.path, serde_json::to_string(credentials)) {
+            let _ = std::fs::write(path, json);
+        }
+    }
+}
@@ -0,0 +1,72 @@
+use std::fmt;
+
+#[derive(Clone, PartialEq)]
+pub enum BackendError {
+    /// The server could not be reached at all.
+    Offline,
+    Timeout,
+    /// The server answered with a non-success status code.
+    Http(u16),
+    /// The response arrived but could not be understood.
+    Parse(String),
+    /// The backend cannot work with the current configuration, e.g. no URL.
+    Misconfigured(String),
+    /// Reading or writing a local leaderboard file failed.
+    Storage(String),
+    /// The identity provider refused to sign this install in or refresh its token.
+    SignIn(String),
+}
+
+impl BackendError {
+    /// Worth retrying automatically; the others need the player or a developer to act.
+    /// 412 means another write to the entry kept winning the race and 429 that we
+    /// were rate limited, so both go through on a later try.
+    pub fn is_transient(&self) -> bool {
+        matches!(
+            self,
+            BackendError::Offline | BackendError::Timeout | BackendError::Http(412 | 429 | 500..=599)
+        )
+    }
+}
+
+impl fmt::Display for BackendError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BackendError::Offline => write!(f, "You appear to be offline"),
+            BackendError::Timeout => write!(f, "The server took too long to respond"),
+            BackendError::Http(status) => write!(f, "The server returned an error (HTTP {})", status),
+            BackendError::Parse(detail) => write!(f, "Received data the game couldn't read: {}", detail),
+            BackendError::Misconfigured(detail) => write!(f, "The leaderboard isn't set up: {}", detail),
+            BackendError::Storage(detail) => write!(f, "Couldn't access the leaderboard file: {}", detail),
+            BackendError::SignIn(detail) => write!(f, "Couldn't sign in to the leaderboard: {}", detail),
+        }
+    }
+}
+
+impl From<reqwest::Error> for BackendError {
+    fn from(err: reqwest::Error) -> Self {
+        if err.is_timeout() {
+            BackendError::Timeout
+        } else if let Some(status) = err.status() {
+            BackendError::Http(status.as_u16())
+        } else if err.is_decode() {
+            BackendError::Parse(err.to_string())
+        } else if err.is_builder() {
+            BackendError::Misconfigured(err.to_string())
+        } else {
+            BackendError::Offline
+        }
+    }
+}
+
+impl From<serde_json::Error> for BackendError {
+    fn from(err: serde_json::Error) -> Self {
+        BackendError::Parse(err.to_string())
+    }
+}
+
+impl From<std::io::Error> for BackendError {
+    fn from(err: std::io::Error) -> Self {
+        BackendError::Storage(err.to_string())
+    }
+}
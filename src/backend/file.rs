@@ -0,0 +1,154 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use tokio::sync::Mutex;
+use crate::resources::LeaderboardEntry;
+use super::local::LocalBoards;
+use super::{
+    entries_of, page_of, standing_of, BackendError, BoardId, BoxFuture, LeaderboardBackend, LeaderboardPage, PageCursor,
+    PlayerStanding,
+};
+
+/// Stores the leaderboards as a JSON file keyed by the paths Firebase uses, so it
+/// can live on a shared drive for LAN play or stand in for the server offline.
+/// Friend lists aren't shared through the file.
+///
+/// Writes hold an OS lock on a `.lock` file beside it, so games on other
+/// processes or machines wait their turn instead of overwriting each other. On
+/// a network drive that only holds if the share honours file locks.
+pub struct FileBackend {
+    path: PathBuf,
+    /// Keeps writes from this process from queueing up on the OS lock.
+    lock: Mutex<()>,
+}
+
+impl FileBackend {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            lock: Mutex::new(()),
+        }
+    }
+
+    async fn read(&self) -> Result<LocalBoards, BackendError> {
+        match tokio::fs::read_to_string(&self.path).await {
+            Ok(text) if text.trim().is_empty() || text.trim() == "null" => Ok(LocalBoards::default()),
+            // Files written before time-windowed boards hold only the all-time map.
+            Ok(text) => match serde_json::from_str(&text) {
+                Ok(boards) => Ok(boards),
+                Err(_) => Ok(LocalBoards::with_all_time(serde_json::from_str::<HashMap<String, LeaderboardEntry>>(&text)?)),
+            },
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(LocalBoards::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Blocks until no other writer holds the lock file. The lock is released
+    /// when the returned handle is dropped.
+    async fn lock_file(&self) -> Result<std::fs::File, BackendError> {
+        let path = self.path.with_extension("json.lock");
+        let locked = tokio::task::spawn_blocking(move || {
+            let file = std::fs::OpenOptions::new().create(true).truncate(false).write(true).open(path)?;
+            file.lock()?;
+            Ok::<_, std::io::Error>(file)
+        })
+        .await
+        .map_err(|e| BackendError::Storage(e.to_string()))?;
+        Ok(locked?)
+    }
+
+    async fn modify<T>(&self, change: impl FnOnce(&mut LocalBoards) -> T) -> Result<T, BackendError> {
+        let _guard = self.lock.lock().await;
+        let _file_lock = self.lock_file().await?;
+        let mut boards = self.read().await?;
+        let result = change(&mut boards);
+
+        let json = serde_json::to_string_pretty(&boards)?;
+        // Write then rename so other readers never see a half-written file.
+        let temp = self.path.with_extension("json.tmp");
+        tokio::fs::write(&temp, json).await?;
+        tokio::fs::rename(&temp, &self.path).await?;
+        Ok(result)
+    }
+}
+
+impl LeaderboardBackend for FileBackend {
+    fn fetch_page<'a>(
+        &'a self,
+        board: &'a BoardId,
+        after: Option<&'a PageCursor>,
+        limit: usize,
+    ) -> BoxFuture<'a, Result<LeaderboardPage, BackendError>> {
+        Box::pin(async move {
+            let entries = self.read().await?.entries(board);
+            Ok(page_of(entries, after, limit))
+        })
+    }
+
+    fn submit_score(&self, entry: LeaderboardEntry) -> BoxFuture<'_, Result<LeaderboardEntry, BackendError>> {
+        Box::pin(self.modify(move |boards| boards.submit_score(entry)))
+    }
+
+    fn update_profile(&self, entry: LeaderboardEntry) -> BoxFuture<'_, Result<(), BackendError>> {
+        Box::pin(self.modify(move |boards| boards.update_profile(entry)))
+    }
+
+    fn fetch_standing<'a>(
+        &'a self,
+        board: &'a BoardId,
+        user_id: &'a str,
+        radius: usize,
+    ) -> BoxFuture<'a, Result<Option<PlayerStanding>, BackendError>> {
+        Box::pin(async move {
+            let entries = self.read().await?.entries(board);
+            Ok(standing_of(entries, user_id, radius))
+        })
+    }
+
+    fn fetch_entries<'a>(
+        &'a self,
+        board: &'a BoardId,
+        user_ids: &'a [String],
+    ) -> BoxFuture<'a, Result<Vec<LeaderboardEntry>, BackendError>> {
+        Box::pin(async move {
+            let entries = self.read().await?.entries(board);
+            Ok(entries_of(entries, user_ids))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use crate::resources::{GameMode, UserProfile};
+    use super::*;
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn racing_writers_never_lower_the_best() {
+        let path = std::env::temp_dir().join(format!("snake_test_race_{}.json", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let profile = UserProfile { user_id: "player".to_string(), ..UserProfile::default() };
+
+        // Two backends on one file behave like two games sharing it, each with
+        // its own in-process lock.
+        let backends = [Arc::new(FileBackend::new(&path)), Arc::new(FileBackend::new(&path))];
+        let tasks: Vec<_> = (1..=40u32)
+            .map(|score| {
+                let backend = backends[score as usize % 2].clone();
+                let entry = LeaderboardEntry::new(&profile, GameMode::Classic, score);
+                tokio::spawn(async move { (score, backend.submit_score(entry).await.ok().unwrap().score) })
+            })
+            .collect();
+
+        for task in tasks {
+            let (submitted, stored) = task.await.unwrap();
+            assert!(stored >= submitted);
+        }
+
+        let board = BoardId::all_time(GameMode::Classic);
+        let stored = backends[0].fetch_entries(&board, &["player".to_string()]).await.ok().unwrap();
+        assert_eq!(stored[0].score, 40);
+
+        let _ = std::fs::remove_file(&path);
+        let _ = std::fs::remove_file(path.with_extension("json.lock"));
+    }
+}
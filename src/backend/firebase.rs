@@ -0,0 +1,479 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+use std::time::Duration;
+use futures_util::future::join_all;
+use serde::Serialize;
+use crate::friends::SyncedFriends;
+use crate::resources::LeaderboardEntry;
+use super::{
+    cursor_after, is_after, merge_best, profile_boards, sort_entries, with_profile, BackendError, BoardId, BoxFuture,
+    LeaderboardBackend, LeaderboardPage, PageCursor, PlayerStanding, Session,
+};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+/// Conditional writes that lose this many races in a row give up.
+const MAX_WRITE_ATTEMPTS: usize = 5;
+/// Players fetched at or above a score to rank it. Anyone further down is shown
+/// as "#1000+" rather than downloading the whole board.
+const MAX_COUNTED_RANK: usize = 1000;
+
+/// Talks to the Firebase Realtime Database REST API. Queries are ordered on the
+/// server, which needs `".indexOn": ["score"]` on every board path in the rules,
+/// see [`BoardId::path`].
+///
+/// Writes are signed with this install's ID token. Before its first write for a
+/// player the backend claims `owners/<user_id>` for the signed-in account, and
+/// the rules in `database.rules.json` only accept board entries and friend lists
+/// under a player id from the account that owns it. A player id is bound once,
+/// so a new daily or weekly board doesn't reopen the question of who owns it.
+///
+/// Players from before sign-in are claimed by whichever account writes for them
+/// first, so the app claims its player with a profile update on first sign-in.
+pub struct FirebaseBackend {
+    base_url: String,
+    client: reqwest::Client,
+    session: Session,
+    /// Player ids this session has already claimed.
+    claimed: Mutex<HashSet<String>>,
+}
+
+/// A value read along with the ETag it was stored under.
+struct Versioned<T> {
+    etag: String,
+    value: Option<T>,
+}
+
+impl FirebaseBackend {
+    /// Rejects anything that isn't an absolute http(s) URL up front, so a bad
+    /// build shows up as misconfiguration instead of a string of failed requests.
+    pub fn new(base_url: &str, session: Session) -> Result<Self, BackendError> {
+        let url = reqwest::Url::parse(base_url)
+            .map_err(|e| BackendError::Misconfigured(format!("invalid Firebase URL \"{}\": {}", base_url, e)))?;
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err(BackendError::Misconfigured(format!("Firebase URL must use http or https, got \"{}\"", base_url)));
+        }
+
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .map_err(|e| BackendError::Misconfigured(e.to_string()))?;
+
+        let mut base_url = base_url.to_string();
+        if !base_url.ends_with('/') {
+            base_url.push('/');
+        }
+
+        Ok(Self { base_url, client, session, claimed: Mutex::new(HashSet::new()) })
+    }
+
+    /// Runs an ordered query against a board. Firebase returns the matches as an
+    /// unordered map, so the caller sorts them.
+    async fn query(&self, board: &BoardId, params: &[(&str, String)]) -> Result<Vec<LeaderboardEntry>, BackendError> {
+        let url = format!("{}{}.json", self.base_url, board.path());
+        let text = self
+            .client
+            .get(&url)
+            .query(&[("orderBy", "\"score\"")])
+            .query(params)
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
+
+        if text == "null" {
+            return Ok(Vec::new());
+        }
+
+        let map: HashMap<String, LeaderboardEntry> = serde_json::from_str(&text)?;
+        Ok(map.into_values().collect())
+    }
+
+    fn entry_url(&self, board: &BoardId, user_id: &str) -> String {
+        format!("{}{}/{}.json", self.base_url, board.path(), user_id)
+    }
+
+    async fn fetch_entry(&self, board: &BoardId, user_id: &str) -> Result<Option<LeaderboardEntry>, BackendError> {
+        let url = self.entry_url(board, user_id);
+        let text = self.client.get(&url).send().await?.error_for_status()?.text().await?;
+        Ok(serde_json::from_str(&text)?)
+    }
+
+    /// Writes `value` as the signed-in account, only if the stored value still
+    /// has the ETag `if_match` when one is given. A rejected token has most likely
+    /// expired early or been revoked, so it's refreshed and the write tried once
+    /// more. The response is left for the caller to check.
+    async fn put_signed<T: Serialize>(
+        &self,
+        url: &str,
+        value: &T,
+        if_match: Option<&str>,
+    ) -> Result<reqwest::Response, BackendError> {
+        let mut retried = false;
+        loop {
+            let credentials = self.session.credentials().await?;
+            let mut request = self
+                .client
+                .put(url)
+                .query(&[("auth", &credentials.id_token)])
+                .json(value);
+            if let Some(etag) = if_match {
+                request = request.header(reqwest::header::IF_MATCH, etag);
+            }
+            let response = request.send().await?;
+
+            if response.status() == reqwest::StatusCode::UNAUTHORIZED && !retried {
+                self.session.expire(&credentials).await;
+                retried = true;
+                continue;
+            }
+
+            return Ok(response);
+        }
+    }
+
+    /// Binds `user_id` to the signed-in account, unless this session already has.
+    /// The rules accept the write when the id is unclaimed or already ours, so
+    /// it's refused only when another account got there first.
+    async fn claim(&self, user_id: &str) -> Result<(), BackendError> {
+        if self.claimed.lock().unwrap().contains(user_id) {
+            return Ok(());
+        }
+
+        let uid = self.session.credentials().await?.uid;
+        let url = format!("{}owners/{}.json", self.base_url, user_id);
+        self.put_signed(&url, &uid, None).await?.error_for_status()?;
+
+        self.claimed.lock().unwrap().insert(user_id.to_string());
+        Ok(())
+    }
+
+    async fn fetch_versioned(&self, url: &str) -> Result<Versioned<LeaderboardEntry>, BackendError> {
+        let response = self
+            .client
+            .get(url)
+            .header("X-Firebase-ETag", "true")
+            .send()
+            .await?
+            .error_for_status()?;
+        read_versioned(response).await
+    }
+
+    /// Replaces a player's entry on `board` with `update(current)`, or leaves it
+    /// alone if that's `None`. Each write only lands if nobody else has written
+    /// since it was read; when someone has, the update is re-applied to what
+    /// they stored. Returns the entry as stored afterwards.
+    async fn update_entry(
+        &self,
+        board: &BoardId,
+        user_id: &str,
+        update: impl Fn(Option<LeaderboardEntry>) -> Option<LeaderboardEntry>,
+    ) -> Result<Option<LeaderboardEntry>, BackendError> {
+        self.claim(user_id).await?;
+        let url = self.entry_url(board, user_id);
+        let mut current = self.fetch_versioned(&url).await?;
+
+        for _ in 0..MAX_WRITE_ATTEMPTS {
+            let Some(entry) = update(current.value.clone()) else {
+                return Ok(current.value);
+            };
+
+            let response = self.put_signed(&url, &entry, Some(&current.etag)).await?;
+            if response.status() == reqwest::StatusCode::PRECONDITION_FAILED {
+                // The rejection carries the newer value and its ETag.
+                current = read_versioned(response).await?;
+                continue;
+            }
+
+            response.error_for_status()?;
+            return Ok(Some(entry));
+        }
+
+        Err(BackendError::Http(reqwest::StatusCode::PRECONDITION_FAILED.as_u16()))
+    }
+
+    async fn submit_to_boards(&self, entry: LeaderboardEntry) -> Result<LeaderboardEntry, BackendError> {
+        let mut stored = None;
+        for board in BoardId::all_at(entry.mode, entry.submitted_at) {
+            stored = self
+                .update_entry(&board, &entry.user_id, |existing| Some(merge_best(existing, entry.clone())))
+                .await?;
+        }
+        // The all-time board comes last.
+        Ok(stored.unwrap_or(entry))
+    }
+
+    /// Each board is its own entry, so they're all updated at once.
+    async fn update_profile_on_boards(&self, entry: LeaderboardEntry) -> Result<(), BackendError> {
+        // Claimed up front so the updates don't all race to do it.
+        self.claim(&entry.user_id).await?;
+        let updates = profile_boards(entry.submitted_at).map(|board| {
+            let entry = &entry;
+            async move {
+                self.update_entry(&board, &entry.user_id, |existing| existing.map(|e| with_profile(e, entry)))
+                    .await
+            }
+        });
+        for result in join_all(updates).await {
+            result?;
+        }
+        Ok(())
+    }
+}
+
+impl LeaderboardBackend for FirebaseBackend {
+    fn fetch_page<'a>(
+        &'a self,
+        board: &'a BoardId,
+        after: Option<&'a PageCursor>,
+        limit: usize,
+    ) -> BoxFuture<'a, Result<LeaderboardPage, BackendError>> {
+        Box::pin(async move {
+            // Entries already shown at the cursor's score come back again with
+            // `endAt`, so ask for that many extra and drop them.
+            let requested = limit + after.map_or(0, |c| c.seen_at_score.len());
+            let mut params = vec![("limitToLast", requested.to_string())];
+            if let Some(cursor) = after {
+                params.push(("endAt", cursor.score.to_string()));
+            }
+
+            let fetched = self.query(board, &params).await?;
+            let has_more = fetched.len() == requested;
+
+            let mut entries: Vec<_> = fetched
+                .into_iter()
+                .filter(|e| after.is_none_or(|c| is_after(e, c)))
+                .collect();
+            sort_entries(&mut entries);
+            entries.truncate(limit);
+
+            let next = if has_more { cursor_after(&entries, after) } else { None };
+            Ok(LeaderboardPage { entries, next })
+        })
+    }
+
+    fn submit_score(&self, entry: LeaderboardEntry) -> BoxFuture<'_, Result<LeaderboardEntry, BackendError>> {
+        Box::pin(self.submit_to_boards(entry))
+    }
+
+    fn update_profile(&self, entry: LeaderboardEntry) -> BoxFuture<'_, Result<(), BackendError>> {
+        Box::pin(self.update_profile_on_boards(entry))
+    }
+
+    fn fetch_standing<'a>(
+        &'a self,
+        board: &'a BoardId,
+        user_id: &'a str,
+        radius: usize,
+    ) -> BoxFuture<'a, Result<Option<PlayerStanding>, BackendError>> {
+        Box::pin(async move {
+            let Some(player) = self.fetch_entry(board, user_id).await? else {
+                return Ok(None);
+            };
+            let key = |e: &LeaderboardEntry| (e.score, e.user_id.clone());
+            let player_key = key(&player);
+
+            // The REST API can't count, so the rank comes from fetching the players
+            // at or above the player's score, closest first, up to a limit.
+            let at_or_above = self
+                .query(board, &[
+                    ("startAt", player.score.to_string()),
+                    ("limitToFirst", MAX_COUNTED_RANK.to_string()),
+                ])
+                .await?;
+            let capped = at_or_above.len() == MAX_COUNTED_RANK;
+            let mut above: Vec<_> = at_or_above.into_iter().filter(|e| key(e) > player_key).collect();
+            sort_entries(&mut above);
+
+            let ties_above = above.iter().filter(|e| e.score == player.score).count();
+            let mut below: Vec<_> = self
+                .query(board, &[
+                    ("endAt", player.score.to_string()),
+                    ("limitToLast", (ties_above + 1 + radius).to_string()),
+                ])
+                .await?
+                .into_iter()
+                .filter(|e| key(e) < player_key)
+                .collect();
+            sort_entries(&mut below);
+            below.truncate(radius);
+
+            let rank = above.len() + 1;
+            let mut entries = above.split_off(above.len().saturating_sub(radius));
+            let first_rank = rank - entries.len();
+            entries.push(player);
+            entries.extend(below);
+
+            Ok(Some(PlayerStanding { rank, first_rank, capped, entries }))
+        })
+    }
+
+    fn fetch_entries<'a>(
+        &'a self,
+        board: &'a BoardId,
+        user_ids: &'a [String],
+    ) -> BoxFuture<'a, Result<Vec<LeaderboardEntry>, BackendError>> {
+        Box::pin(async move {
+            let mut entries = Vec::new();
+            for user_id in user_ids {
+                if let Some(entry) = self.fetch_entry(board, user_id).await? {
+                    entries.push(entry);
+                }
+            }
+            sort_entries(&mut entries);
+            Ok(entries)
+        })
+    }
+
+    fn fetch_friends<'a>(&'a self, user_id: &'a str) -> BoxFuture<'a, Result<SyncedFriends, BackendError>> {
+        Box::pin(async move {
+            let url = format!("{}friends/{}.json", self.base_url, user_id);
+            let text = self.client.get(&url).send().await?.error_for_status()?.text().await?;
+            let friends: Option<SyncedFriends> = serde_json::from_str(&text)?;
+            Ok(friends.unwrap_or_default())
+        })
+    }
+
+    fn save_friends(&self, user_id: String, friends: SyncedFriends) -> BoxFuture<'_, Result<(), BackendError>> {
+        Box::pin(async move {
+            self.claim(&user_id).await?;
+            let url = format!("{}friends/{}.json", self.base_url, user_id);
+            self.put_signed(&url, &friends, None).await?.error_for_status()?;
+            Ok(())
+        })
+    }
+}
+
+async fn read_versioned(response: reqwest::Response) -> Result<Versioned<LeaderboardEntry>, BackendError> {
+    let etag = response
+        .headers()
+        .get(reqwest::header::ETAG)
+        .and_then(|v| v.to_str().ok())
+        .ok_or_else(|| BackendError::Parse("response had no ETag".to_string()))?
+        .to_string();
+    let text = response.text().await?;
+    Ok(Versioned { etag, value: serde_json::from_str(&text)? })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::path::PathBuf;
+    use std::sync::{Arc, Mutex};
+    use super::super::{Credentials, StubIdentityServer};
+    use super::*;
+
+    /// A write as the stand-in database received it.
+    struct ReceivedWrite {
+        path: String,
+        id_token: String,
+        body: serde_json::Value,
+    }
+
+    /// Answers every PUT, refusing the first one and any whose token the identity
+    /// server doesn't vouch for with 401, like a database whose rules need `auth`.
+    fn start_database(identity: Arc<StubIdentityServer>) -> (String, Arc<Mutex<Vec<ReceivedWrite>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        let writes = Arc::new(Mutex::new(Vec::new()));
+
+        let received = writes.clone();
+        std::thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let mut reader = BufReader::new(stream);
+                let mut request_line = String::new();
+                reader.read_line(&mut request_line).unwrap();
+
+                let mut content_length = 0;
+                loop {
+                    let mut header = String::new();
+                    if reader.read_line(&mut header).unwrap() == 0 || header.trim().is_empty() {
+                        break;
+                    }
+                    if let Some((name, value)) = header.split_once(':') {
+                        if name.eq_ignore_ascii_case("content-length") {
+                            content_length = value.trim().parse().unwrap();
+                        }
+                    }
+                }
+                let mut body = vec![0; content_length];
+                reader.read_exact(&mut body).unwrap();
+
+                let target = request_line.split_whitespace().nth(1).unwrap_or_default();
+                let (path, query) = target.split_once('?').unwrap_or((target, ""));
+                let id_token = query.split_once("auth=").map(|(_, token)| token).unwrap_or_default().to_string();
+                let mut received = received.lock().unwrap();
+                let first = received.is_empty();
+                let authorised = identity.verify(&id_token).is_some();
+                received.push(ReceivedWrite { path: path.to_string(), id_token, body: serde_json::from_slice(&body).unwrap() });
+                drop(received);
+
+                let status = if first || !authorised { "401 Unauthorized" } else { "200 OK" };
+                let _ = write!(
+                    reader.get_mut(),
+                    "HTTP/1.1 {}\r\nContent-Length: 4\r\nConnection: close\r\n\r\nnull",
+                    status
+                );
+            }
+        });
+
+        (url, writes)
+    }
+
+    fn credentials_path(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("snake_test_{}_{}.json", name, std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+
+    #[tokio::test]
+    async fn sessions_sign_in_once_and_refresh_expiring_tokens() {
+        // Tokens this short-lived are always inside the refresh margin.
+        let identity = StubIdentityServer::start(30).unwrap();
+        let path = credentials_path("refresh");
+
+        let session = Session::new(Box::new(identity.provider()), Some(path.clone()));
+        let first = session.credentials().await.ok().unwrap();
+        assert_eq!(identity.verify(&first.id_token), Some(first.uid.clone()));
+
+        let refreshed = session.credentials().await.ok().unwrap();
+        assert_eq!(refreshed.uid, first.uid);
+        assert_ne!(refreshed.id_token, first.id_token);
+
+        // A restart picks the same account back up from disk.
+        let restarted = Session::new(Box::new(identity.provider()), Some(path.clone()));
+        assert_eq!(restarted.credentials().await.ok().unwrap().uid, first.uid);
+
+        // A server that doesn't know the refresh token refuses it, and the account
+        // is kept rather than swapped for a new one.
+        let other = StubIdentityServer::start(30).unwrap();
+        let refused = Session::new(Box::new(other.provider()), Some(path.clone()));
+        assert!(matches!(refused.credentials().await, Err(BackendError::SignIn(_))));
+        let saved: Credentials = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(saved.uid, first.uid);
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[tokio::test]
+    async fn writes_claim_the_player_once_and_retry_with_a_refreshed_token() {
+        let identity = Arc::new(StubIdentityServer::start(3600).unwrap());
+        let (url, writes) = start_database(identity.clone());
+
+        let backend = FirebaseBackend::new(&url, Session::new(Box::new(identity.provider()), None)).ok().unwrap();
+        let friends = SyncedFriends { ids: vec!["friend".to_string()], modified_at: 1 };
+        backend.save_friends("player".to_string(), friends).await.ok().unwrap();
+        backend.save_friends("player".to_string(), SyncedFriends::default()).await.ok().unwrap();
+
+        let writes = writes.lock().unwrap();
+        let paths: Vec<_> = writes.iter().map(|w| w.path.as_str()).collect();
+        assert_eq!(paths, ["/owners/player.json", "/owners/player.json", "/friends/player.json", "/friends/player.json"]);
+
+        let (rejected, retried) = (&writes[0], &writes[1]);
+        assert_ne!(rejected.id_token, retried.id_token);
+        let uid = identity.verify(&retried.id_token).unwrap();
+        assert_eq!(retried.body, serde_json::json!(uid));
+        assert_eq!(writes[2].body["ids"], serde_json::json!(["friend"]));
+    }
+}
@@ -0,0 +1,157 @@
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+use crate::period;
+use super::auth::FirebaseIdentity;
+
+/// The stand-in doesn't check API keys, but the client always sends one.
+const STUB_API_KEY: &str = "stub";
+
+#[derive(Default)]
+struct StubAccounts {
+    token_lifetime: u64,
+    /// Refresh token to account.
+    refresh_tokens: HashMap<String, String>,
+    /// ID token to account and expiry.
+    id_tokens: HashMap<String, (String, u64)>,
+}
+
+impl StubAccounts {
+    /// A fresh ID and refresh token pair for `uid`, replacing its old refresh token.
+    fn issue(&mut self, uid: &str) -> (String, String) {
+        self.refresh_tokens.retain(|_, owner| owner != uid);
+        let id_token = random_token(32);
+        let refresh_token = random_token(32);
+        let expires_at = period::now() + self.token_lifetime;
+        self.id_tokens.insert(id_token.clone(), (uid.to_string(), expires_at));
+        self.refresh_tokens.insert(refresh_token.clone(), uid.to_string());
+        (id_token, refresh_token)
+    }
+}
+
+/// A tiny local server speaking just enough of Firebase Authentication's REST
+/// API for anonymous sign-in and token refresh, so the sign-in flow can be
+/// exercised without a Firebase project. Tokens can be made to expire quickly
+/// to see refreshes happen. Accounts live in memory and are lost when the
+/// process exits.
+pub struct StubIdentityServer {
+    url: String,
+    accounts: Arc<Mutex<StubAccounts>>,
+}
+
+impl StubIdentityServer {
+    /// Listens on a free port on localhost, handing out tokens that last
+    /// `token_lifetime` seconds.
+    pub fn start(token_lifetime: u64) -> std::io::Result<Self> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let url = format!("http://{}", listener.local_addr()?);
+        let accounts = Arc::new(Mutex::new(StubAccounts { token_lifetime, ..StubAccounts::default() }));
+
+        let shared = accounts.clone();
+        std::thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let _ = handle(stream, &shared);
+            }
+        });
+
+        Ok(Self { url, accounts })
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// A provider that signs in against this server.
+    pub fn provider(&self) -> FirebaseIdentity {
+        FirebaseIdentity::with_endpoints(&self.url, &self.url, STUB_API_KEY)
+    }
+
+    /// The account behind `id_token`, if it was issued here and hasn't expired.
+    pub fn verify(&self, id_token: &str) -> Option<String> {
+        let accounts = self.accounts.lock().unwrap();
+        let (uid, expires_at) = accounts.id_tokens.get(id_token)?;
+        (*expires_at > period::now()).then(|| uid.clone())
+    }
+}
+
+fn handle(stream: TcpStream, accounts: &Mutex<StubAccounts>) -> std::io::Result<()> {
+    let mut reader = BufReader::new(stream);
+
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    let path = request_line.split_whitespace().nth(1).unwrap_or_default().to_string();
+
+    let mut content_length = 0;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 || header.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse().unwrap_or(0);
+            }
+        }
+    }
+
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body)?;
+    let body = String::from_utf8_lossy(&body);
+
+    let route = path.split('?').next().unwrap_or_default();
+    let mut accounts = accounts.lock().unwrap();
+    let (status, response) = match route {
+        "/v1/accounts:signUp" => {
+            let uid = random_token(28);
+            let (id_token, refresh_token) = accounts.issue(&uid);
+            (200, serde_json::json!({
+                "localId": uid,
+                "idToken": id_token,
+                "refreshToken": refresh_token,
+                "expiresIn": accounts.token_lifetime.to_string(),
+            }))
+        }
+        "/v1/token" => {
+            let refresh_token = body
+                .split('&')
+                .find_map(|pair| pair.strip_prefix("refresh_token="))
+                .unwrap_or_default();
+            match accounts.refresh_tokens.get(refresh_token).cloned() {
+                Some(uid) => {
+                    let (id_token, refresh_token) = accounts.issue(&uid);
+                    (200, serde_json::json!({
+                        "user_id": uid,
+                        "id_token": id_token,
+                        "refresh_token": refresh_token,
+                        "expires_in": accounts.token_lifetime.to_string(),
+                    }))
+                }
+                None => (400, error_body("INVALID_REFRESH_TOKEN")),
+            }
+        }
+        _ => (404, error_body("NOT_FOUND")),
+    };
+    drop(accounts);
+
+    let response = response.to_string();
+    let reason = if status == 200 { "OK" } else { "Error" };
+    write!(
+        reader.get_mut(),
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        reason,
+        response.len(),
+        response
+    )
+}
+
+fn error_body(message: &str) -> serde_json::Value {
+    serde_json::json!({ "error": { "message": message } })
+}
+
+fn random_token(len: usize) -> String {
+    rand::thread_rng().sample_iter(&Alphanumeric).take(len).map(char::from).collect()
+}
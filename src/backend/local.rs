@@ -0,0 +1,39 @@
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use crate::resources::{GameMode, LeaderboardEntry};
+use super::{merge_best, profile_boards, with_profile, BoardId};
+
+/// Every board the in-process backends keep, keyed by [`BoardId::path`] and
+/// then by user id.
+#[derive(Serialize, Deserialize, Default)]
+#[serde(transparent)]
+pub(crate) struct LocalBoards(HashMap<String, HashMap<String, LeaderboardEntry>>);
+
+impl LocalBoards {
+    /// Seeds the Classic all-time board.
+    pub fn with_all_time(entries: HashMap<String, LeaderboardEntry>) -> Self {
+        Self(HashMap::from([(BoardId::all_time(GameMode::Classic).path(), entries)]))
+    }
+
+    pub fn entries(&self, board: &BoardId) -> Vec<LeaderboardEntry> {
+        self.0.get(&board.path()).map(|b| b.values().cloned().collect()).unwrap_or_default()
+    }
+
+    /// Returns the player's all-time entry for the mode afterwards.
+    pub fn submit_score(&mut self, entry: LeaderboardEntry) -> LeaderboardEntry {
+        for board in BoardId::all_at(entry.mode, entry.submitted_at) {
+            let board = self.0.entry(board.path()).or_default();
+            let existing = board.remove(&entry.user_id);
+            board.insert(entry.user_id.clone(), merge_best(existing, entry.clone()));
+        }
+        self.0[&BoardId::all_time(entry.mode).path()][&entry.user_id].clone()
+    }
+
+    pub fn update_profile(&mut self, entry: LeaderboardEntry) {
+        for id in profile_boards(entry.submitted_at) {
+            if let Some(existing) = self.0.get_mut(&id.path()).and_then(|board| board.get_mut(&entry.user_id)) {
+                *existing = with_profile(existing.clone(), &entry);
+            }
+        }
+    }
+}
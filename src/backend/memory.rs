@@ -0,0 +1,82 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use crate::friends::SyncedFriends;
+use crate::resources::LeaderboardEntry;
+use super::local::LocalBoards;
+use super::{
+    entries_of, page_of, standing_of, BackendError, BoardId, BoxFuture, LeaderboardBackend, LeaderboardPage, PageCursor,
+    PlayerStanding,
+};
+
+/// Keeps everything in memory. Handy for tests and for playing without a server.
+#[derive(Default)]
+pub struct MemoryBackend {
+    boards: Mutex<LocalBoards>,
+    friends: Mutex<HashMap<String, SyncedFriends>>,
+}
+
+impl MemoryBackend {
+    /// Seeds the all-time board.
+    pub fn with_entries(entries: Vec<LeaderboardEntry>) -> Self {
+        let entries = entries.into_iter().map(|e| (e.user_id.clone(), e)).collect();
+        Self {
+            boards: Mutex::new(LocalBoards::with_all_time(entries)),
+            ..Self::default()
+        }
+    }
+
+    fn snapshot(&self, board: &BoardId) -> Vec<LeaderboardEntry> {
+        self.boards.lock().unwrap().entries(board)
+    }
+}
+
+impl LeaderboardBackend for MemoryBackend {
+    fn fetch_page<'a>(
+        &'a self,
+        board: &'a BoardId,
+        after: Option<&'a PageCursor>,
+        limit: usize,
+    ) -> BoxFuture<'a, Result<LeaderboardPage, BackendError>> {
+        let page = page_of(self.snapshot(board), after, limit);
+        Box::pin(async move { Ok(page) })
+    }
+
+    fn submit_score(&self, entry: LeaderboardEntry) -> BoxFuture<'_, Result<LeaderboardEntry, BackendError>> {
+        let stored = self.boards.lock().unwrap().submit_score(entry);
+        Box::pin(async move { Ok(stored) })
+    }
+
+    fn update_profile(&self, entry: LeaderboardEntry) -> BoxFuture<'_, Result<(), BackendError>> {
+        self.boards.lock().unwrap().update_profile(entry);
+        Box::pin(async { Ok(()) })
+    }
+
+    fn fetch_standing<'a>(
+        &'a self,
+        board: &'a BoardId,
+        user_id: &'a str,
+        radius: usize,
+    ) -> BoxFuture<'a, Result<Option<PlayerStanding>, BackendError>> {
+        let standing = standing_of(self.snapshot(board), user_id, radius);
+        Box::pin(async move { Ok(standing) })
+    }
+
+    fn fetch_entries<'a>(
+        &'a self,
+        board: &'a BoardId,
+        user_ids: &'a [String],
+    ) -> BoxFuture<'a, Result<Vec<LeaderboardEntry>, BackendError>> {
+        let entries = entries_of(self.snapshot(board), user_ids);
+        Box::pin(async move { Ok(entries) })
+    }
+
+    fn fetch_friends<'a>(&'a self, user_id: &'a str) -> BoxFuture<'a, Result<SyncedFriends, BackendError>> {
+        let friends = self.friends.lock().unwrap().get(user_id).cloned().unwrap_or_default();
+        Box::pin(async move { Ok(friends) })
+    }
+
+    fn save_friends(&self, user_id: String, friends: SyncedFriends) -> BoxFuture<'_, Result<(), BackendError>> {
+        self.friends.lock().unwrap().insert(user_id, friends);
+        Box::pin(async { Ok(()) })
+    }
+}
@@ -0,0 +1,305 @@
+pub mod auth;
+pub mod error;
+pub mod file;
+pub mod firebase;
+pub mod identity_stub;
+mod local;
+pub mod memory;
+pub mod outbox;
+
+use std::future::Future;
+use std::path::PathBuf;
+use std::pin::Pin;
+use serde::{Deserialize, Serialize};
+use crate::config::{BackendConfig, IdentitySource, LeaderboardSource};
+use crate::friends::SyncedFriends;
+use crate::period::LeaderboardPeriod;
+use crate::resources::{GameMode, LeaderboardEntry};
+
+pub use auth::{Credentials, FirebaseIdentity, IdentityProvider, Session};
+pub use error::BackendError;
+pub use file::FileBackend;
+pub use firebase::FirebaseBackend;
+pub use identity_stub::StubIdentityServer;
+pub use memory::MemoryBackend;
+pub use outbox::{FlushResult, Outbox, OutboxItem};
+
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// One leaderboard: a mode's all-time board, or a single day or week of its
+/// time-windowed boards. Past buckets are never deleted, which is what keeps
+/// previous winners around.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct BoardId {
+    pub mode: GameMode,
+    pub period: LeaderboardPeriod,
+    /// See [`LeaderboardPeriod::bucket`].
+    pub bucket: String,
+}
+
+impl BoardId {
+    pub fn all_time(mode: GameMode) -> Self {
+        Self { mode, ..Self::default() }
+    }
+
+    pub fn current(mode: GameMode, period: LeaderboardPeriod, now: u64) -> Self {
+        Self { mode, period, bucket: period.bucket(now) }
+    }
+
+    pub fn previous(mode: GameMode, period: LeaderboardPeriod, now: u64) -> Option<Self> {
+        Some(Self { mode, period, bucket: period.previous_bucket(now)? })
+    }
+
+    /// Every board of `mode` that a score submitted at `time` counts towards.
+    pub fn all_at(mode: GameMode, time: u64) -> [Self; 3] {
+        LeaderboardPeriod::ALL.map(|period| Self::current(mode, period, time))
+    }
+
+    /// Location in the database, also used as the key in local storage. Classic
+    /// keeps the original top-level paths so scores from before modes stay put.
+    pub fn path(&self) -> String {
+        let board = match self.period {
+            LeaderboardPeriod::Daily => format!("leaderboard_daily/{}", self.bucket),
+            LeaderboardPeriod::Weekly => format!("leaderboard_weekly/{}", self.bucket),
+            LeaderboardPeriod::AllTime => "leaderboard".to_string(),
+        };
+        match self.mode {
+            GameMode::Classic => board,
+        }
+    }
+}
+
+/// Where the next page starts: entries scoring below `score`, plus any at
+/// `score` that haven't been shown yet.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PageCursor {
+    pub score: u32,
+    pub seen_at_score: Vec<String>,
+}
+
+pub struct LeaderboardPage {
+    /// Best first.
+    pub entries: Vec<LeaderboardEntry>,
+    /// `None` once the end of the leaderboard has been reached.
+    pub next: Option<PageCursor>,
+}
+
+/// A player's overall position and the entries either side of it.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct PlayerStanding {
+    /// One-based.
+    pub rank: usize,
+    /// Rank of the first of `entries`.
+    pub first_rank: usize,
+    /// The backend stopped counting the players ahead, so the ranks are only
+    /// lower bounds.
+    #[serde(default)]
+    pub capped: bool,
+    /// Best first, including the player.
+    pub entries: Vec<LeaderboardEntry>,
+}
+
+/// Everything the game needs from a leaderboard service. Futures are boxed so
+/// the backend can be chosen at runtime and kept behind a trait object.
+pub trait LeaderboardBackend: Send + Sync {
+    /// Up to `limit` entries, best first, starting at `after` or the top.
+    fn fetch_page<'a>(
+        &'a self,
+        board: &'a BoardId,
+        after: Option<&'a PageCursor>,
+        limit: usize,
+    ) -> BoxFuture<'a, Result<LeaderboardPage, BackendError>>;
+
+    /// Records the score on every board of `entry.mode` for `entry.submitted_at`,
+    /// keeping each player's best. A stored score never goes down, even with
+    /// several devices writing at once. Returns the player's all-time entry for
+    /// the mode as stored afterwards, which is higher than `entry` when another
+    /// device got there first.
+    fn submit_score(&self, entry: LeaderboardEntry) -> BoxFuture<'_, Result<LeaderboardEntry, BackendError>>;
+
+    /// Pushes name, skin and colour changes to the boards the player is already
+    /// on. The entry's score and mode are ignored.
+    fn update_profile(&self, entry: LeaderboardEntry) -> BoxFuture<'_, Result<(), BackendError>>;
+
+    /// The player's rank with up to `radius` entries above and below, `None` if
+    /// they have no entry yet.
+    fn fetch_standing<'a>(
+        &'a self,
+        board: &'a BoardId,
+        user_id: &'a str,
+        radius: usize,
+    ) -> BoxFuture<'a, Result<Option<PlayerStanding>, BackendError>>;
+
+    /// Entries for just these players, best first. Players without a score on the
+    /// board are left out.
+    fn fetch_entries<'a>(
+        &'a self,
+        board: &'a BoardId,
+        user_ids: &'a [String],
+    ) -> BoxFuture<'a, Result<Vec<LeaderboardEntry>, BackendError>>;
+
+    /// The friend list stored for the player. Backends that don't keep friend
+    /// lists have an empty one that was never modified.
+    fn fetch_friends<'a>(&'a self, _user_id: &'a str) -> BoxFuture<'a, Result<SyncedFriends, BackendError>> {
+        Box::pin(async { Ok(SyncedFriends::default()) })
+    }
+
+    fn save_friends(&self, _user_id: String, _friends: SyncedFriends) -> BoxFuture<'_, Result<(), BackendError>> {
+        Box::pin(async { Ok(()) })
+    }
+}
+
+/// Builds the backend chosen by `config`. Firebase credentials are kept at
+/// `credentials_path`.
+pub fn connect(config: &BackendConfig, credentials_path: Option<PathBuf>) -> Box<dyn LeaderboardBackend> {
+    let backend = match &config.leaderboard {
+        LeaderboardSource::Memory => return Box::new(MemoryBackend::default()),
+        LeaderboardSource::File(path) => return Box::new(FileBackend::new(path.clone())),
+        LeaderboardSource::Firebase { url, identity } => identity_provider(identity)
+            .and_then(|provider| FirebaseBackend::new(url, Session::new(provider, credentials_path))),
+        LeaderboardSource::Unavailable(reason) => Err(BackendError::Misconfigured(reason.clone())),
+    };
+    match backend {
+        Ok(backend) => Box::new(backend),
+        Err(err) => Box::new(Unconfigured(err)),
+    }
+}
+
+fn identity_provider(identity: &IdentitySource) -> Result<Box<dyn IdentityProvider>, BackendError> {
+    match identity {
+        IdentitySource::Firebase { api_key } => Ok(Box::new(FirebaseIdentity::new(api_key))),
+        IdentitySource::Local { token_lifetime } => {
+            let server = StubIdentityServer::start(*token_lifetime)
+                .map_err(|e| BackendError::Misconfigured(format!("couldn't start the local identity server: {}", e)))?;
+            Ok(Box::new(server.provider()))
+        }
+    }
+}
+
+/// Stands in when no usable backend could be built, failing every call with the reason.
+struct Unconfigured(BackendError);
+
+impl Unconfigured {
+    fn fail<'a, T: Send + 'a>(&self) -> BoxFuture<'a, Result<T, BackendError>> {
+        let err = self.0.clone();
+        Box::pin(async move { Err(err) })
+    }
+}
+
+impl LeaderboardBackend for Unconfigured {
+    fn fetch_page<'a>(
+        &'a self,
+        _board: &'a BoardId,
+        _after: Option<&'a PageCursor>,
+        _limit: usize,
+    ) -> BoxFuture<'a, Result<LeaderboardPage, BackendError>> {
+        self.fail()
+    }
+
+    fn submit_score(&self, _entry: LeaderboardEntry) -> BoxFuture<'_, Result<LeaderboardEntry, BackendError>> {
+        self.fail()
+    }
+
+    fn update_profile(&self, _entry: LeaderboardEntry) -> BoxFuture<'_, Result<(), BackendError>> {
+        self.fail()
+    }
+
+    fn fetch_standing<'a>(
+        &'a self,
+        _board: &'a BoardId,
+        _user_id: &'a str,
+        _radius: usize,
+    ) -> BoxFuture<'a, Result<Option<PlayerStanding>, BackendError>> {
+        self.fail()
+    }
+
+    fn fetch_entries<'a>(
+        &'a self,
+        _board: &'a BoardId,
+        _user_ids: &'a [String],
+    ) -> BoxFuture<'a, Result<Vec<LeaderboardEntry>, BackendError>> {
+        self.fail()
+    }
+
+    fn fetch_friends<'a>(&'a self, _user_id: &'a str) -> BoxFuture<'a, Result<SyncedFriends, BackendError>> {
+        self.fail()
+    }
+
+    fn save_friends(&self, _user_id: String, _friends: SyncedFriends) -> BoxFuture<'_, Result<(), BackendError>> {
+        self.fail()
+    }
+}
+
+/// Every board a profile change might need to reach.
+pub(crate) fn profile_boards(time: u64) -> impl Iterator<Item = BoardId> {
+    GameMode::ALL.into_iter().flat_map(move |mode| BoardId::all_at(mode, time))
+}
+
+/// What a board should hold after `entry` arrives: the better of the two scores,
+/// with the newest name, skin and colour either way.
+pub(crate) fn merge_best(existing: Option<LeaderboardEntry>, entry: LeaderboardEntry) -> LeaderboardEntry {
+    match existing {
+        Some(existing) if existing.score >= entry.score => with_profile(existing, &entry),
+        _ => entry,
+    }
+}
+
+/// Keeps the score and timestamp of `existing` but takes the profile fields from `entry`.
+pub(crate) fn with_profile(existing: LeaderboardEntry, entry: &LeaderboardEntry) -> LeaderboardEntry {
+    LeaderboardEntry {
+        username: entry.username.clone(),
+        skin: entry.skin,
+        snake_color: entry.snake_color,
+        ..existing
+    }
+}
+
+/// Best first. Ties go to the higher user id, matching the order Firebase
+/// returns them in, so pages line up whichever backend produced them.
+pub(crate) fn sort_entries(entries: &mut [LeaderboardEntry]) {
+    entries.sort_by(|a, b| (b.score, &b.user_id).cmp(&(a.score, &a.user_id)));
+}
+
+/// Whether `entry` comes after the cursor in leaderboard order.
+pub(crate) fn is_after(entry: &LeaderboardEntry, cursor: &PageCursor) -> bool {
+    entry.score < cursor.score || (entry.score == cursor.score && !cursor.seen_at_score.contains(&entry.user_id))
+}
+
+/// Cursor for the page following `page`, which itself started at `after`.
+pub(crate) fn cursor_after(page: &[LeaderboardEntry], after: Option<&PageCursor>) -> Option<PageCursor> {
+    let last = page.last()?.score;
+    let mut seen_at_score: Vec<String> = match after {
+        Some(cursor) if cursor.score == last => cursor.seen_at_score.clone(),
+        _ => Vec::new(),
+    };
+    seen_at_score.extend(page.iter().filter(|e| e.score == last).map(|e| e.user_id.clone()));
+    Some(PageCursor { score: last, seen_at_score })
+}
+
+/// Pages through a complete list of entries, for backends that hold everything locally.
+pub(crate) fn page_of(mut entries: Vec<LeaderboardEntry>, after: Option<&PageCursor>, limit: usize) -> LeaderboardPage {
+    sort_entries(&mut entries);
+    let mut remaining = entries.into_iter().filter(|e| after.is_none_or(|c| is_after(e, c)));
+    let page: Vec<_> = remaining.by_ref().take(limit).collect();
+    let next = if remaining.next().is_some() { cursor_after(&page, after) } else { None };
+    LeaderboardPage { entries: page, next }
+}
+
+pub(crate) fn entries_of(mut entries: Vec<LeaderboardEntry>, user_ids: &[String]) -> Vec<LeaderboardEntry> {
+    entries.retain(|e| user_ids.contains(&e.user_id));
+    sort_entries(&mut entries);
+    entries
+}
+
+pub(crate) fn standing_of(mut entries: Vec<LeaderboardEntry>, user_id: &str, radius: usize) -> Option<PlayerStanding> {
+    sort_entries(&mut entries);
+    let index = entries.iter().position(|e| e.user_id == user_id)?;
+    let first = index.saturating_sub(radius);
+    let last = (index + radius + 1).min(entries.len());
+    Some(PlayerStanding {
+        rank: index + 1,
+        first_rank: first + 1,
+        capped: false,
+        entries: entries.drain(first..last).collect(),
+    })
+}
@@ -0,0 +1,369 @@
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+use serde::{Deserialize, Serialize};
+use crate::period::LeaderboardPeriod;
+use crate::resources::LeaderboardEntry;
+use super::{merge_best, BackendError, LeaderboardBackend};
+
+const RETRY_BASE_DELAY: Duration = Duration::from_secs(2);
+const RETRY_MAX_DELAY: Duration = Duration::from_secs(300);
+
+#[derive(Serialize, Deserialize, Clone)]
+pub enum OutboxItem {
+    Score(LeaderboardEntry),
+    Profile(LeaderboardEntry),
+}
+
+impl OutboxItem {
+    fn entry(&self) -> &LeaderboardEntry {
+        match self {
+            OutboxItem::Score(entry) | OutboxItem::Profile(entry) => entry,
+        }
+    }
+}
+
+pub enum FlushResult {
+    /// Everything that was queued has been delivered.
+    Done { delivered: usize, confirmed: Vec<LeaderboardEntry> },
+    /// Stopped early; whatever is left stays queued.
+    Failed { delivered: usize, confirmed: Vec<LeaderboardEntry>, error: BackendError },
+}
+
+/// Writes waiting to reach the backend, persisted so they survive going offline
+/// or the app being closed. Failed sends are retried with exponential backoff.
+#[derive(Default)]
+pub struct Outbox {
+    items: Vec<OutboxItem>,
+    path: Option<PathBuf>,
+    failures: u32,
+    next_attempt: Option<Instant>,
+}
+
+impl Outbox {
+    pub fn load(path: Option<PathBuf>) -> Self {
+        let items = path
+            .as_ref()
+            .and_then(|p| std::fs::read_to_string(p).ok())
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default();
+
+        Self {
+            items,
+            path,
+            ..Self::default()
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Queues a write. Only the best unsent score per player, mode and day is kept,
+    /// carrying the newest name and skin, and a newer profile update replaces an
+    /// older one. Scores from different days stay apart so each reaches its own
+    /// daily board.
+    pub fn push(&mut self, item: OutboxItem) {
+        let user_id = item.entry().user_id.clone();
+
+        match item {
+            OutboxItem::Score(mut entry) => {
+                let day = LeaderboardPeriod::Daily.bucket(entry.submitted_at);
+                let existing = self.items.iter().position(|i| {
+                    matches!(i, OutboxItem::Score(e) if e.user_id == user_id
+                        && e.mode == entry.mode
+                        && LeaderboardPeriod::Daily.bucket(e.submitted_at) == day)
+                });
+                if let Some(index) = existing {
+                    if let OutboxItem::Score(old) = self.items.remove(index) {
+                        entry = merge_best(Some(old), entry);
+                    }
+                }
+                self.items.push(OutboxItem::Score(entry));
+            }
+            OutboxItem::Profile(entry) => {
+                self.items.retain(|i| !matches!(i, OutboxItem::Profile(e) if e.user_id == user_id));
+                self.items.push(OutboxItem::Profile(entry));
+            }
+        }
+
+        self.save();
+    }
+
+    /// Forgets the backoff so the next flush happens straight away, e.g. once
+    /// another request has shown that the connection is back.
+    pub fn retry_now(&mut self) {
+        self.failures = 0;
+        self.next_attempt = None;
+    }
+
+    /// How long to wait before the next automatic retry, `None` when nothing is
+    /// scheduled.
+    pub fn retry_delay(&self) -> Option<Duration> {
+        if self.items.is_empty() {
+            return None;
+        }
+        self.next_attempt.map(|at| at.saturating_duration_since(Instant::now()))
+    }
+
+    /// Sends queued writes in order. `confirmed` holds the stored all-time entry
+    /// for each score delivered, so the caller can pick up bests set elsewhere.
+    pub async fn flush(&mut self, backend: &dyn LeaderboardBackend) -> FlushResult {
+        let mut delivered = 0;
+        let mut confirmed = Vec::new();
+
+        while let Some(item) = self.items.first().cloned() {
+            let result = match item {
+                OutboxItem::Score(entry) => backend.submit_score(entry).await.map(Some),
+                OutboxItem::Profile(entry) => backend.update_profile(entry).await.map(|()| None),
+            };
+
+            match result {
+                Ok(stored) => {
+                    self.items.remove(0);
+                    delivered += 1;
+                    confirmed.extend(stored);
+                    self.save();
+                }
+                Err(error) if error.is_transient() => {
+                    self.schedule_retry();
+                    return FlushResult::Failed { delivered, confirmed, error };
+                }
+                // Nothing to retry until the app is configured properly or can sign
+                // in again; the writes are kept for the next start. A 401 or 403 that
+                // survived a token refresh is the same problem seen from the database.
+                Err(error @ (BackendError::Misconfigured(_)
+                | BackendError::SignIn(_)
+                | BackendError::Http(401 | 403))) => {
+                    self.next_attempt = None;
+                    return FlushResult::Failed { delivered, confirmed, error };
+                }
+                // The backend rejected this write outright; resending it won't help.
+                Err(error) => {
+                    self.items.remove(0);
+                    self.save();
+                    return FlushResult::Failed { delivered, confirmed, error };
+                }
+            }
+        }
+
+        self.retry_now();
+        FlushResult::Done { delivered, confirmed }
+    }
+
+    fn schedule_retry(&mut self) {
+        let delay = RETRY_BASE_DELAY
+            .saturating_mul(2u32.saturating_pow(self.failures))
+            .min(RETRY_MAX_DELAY);
+        self.failures = self.failures.saturating_add(1);
+        self.next_attempt = Some(Instant::now() + delay);
+    }
+
+    fn save(&self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        if self.items.is_empty() {
+            let _ = std::fs::remove_file(path);
+        } else if let Ok(json) = serde_json::to_string(&self.items) {
+            // Written aside and renamed over so a crash mid-write can't lose the queue.
+            let temp = path.with_extension("json.tmp");
+            if std::fs::write(&temp, json).is_ok() {
+                let _ = std::fs::rename(&temp, path);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+    use std::sync::Mutex;
+    use crate::resources::{GameMode, UserProfile};
+    use super::super::{BoardId, BoxFuture, LeaderboardPage, MemoryBackend, PageCursor, PlayerStanding};
+    use super::*;
+
+    const DAY: u64 = 24 * 60 * 60;
+
+    /// A memory backend whose next score submissions fail with the queued errors.
+    #[derive(Default)]
+    struct FailingBackend {
+        inner: MemoryBackend,
+        errors: Mutex<VecDeque<BackendError>>,
+    }
+
+    impl FailingBackend {
+        fn fail_next(&self, error: BackendError) {
+            self.errors.lock().unwrap().push_back(error);
+        }
+    }
+
+    impl LeaderboardBackend for FailingBackend {
+        fn fetch_page<'a>(
+            &'a self,
+            board: &'a BoardId,
+            after: Option<&'a PageCursor>,
+            limit: usize,
+        ) -> BoxFuture<'a, Result<LeaderboardPage, BackendError>> {
+            self.inner.fetch_page(board, after, limit)
+        }
+
+        fn submit_score(&self, entry: LeaderboardEntry) -> BoxFuture<'_, Result<LeaderboardEntry, BackendError>> {
+            match self.errors.lock().unwrap().pop_front() {
+                Some(error) => Box::pin(async move { Err(error) }),
+                None => self.inner.submit_score(entry),
+            }
+        }
+
+        fn update_profile(&self, entry: LeaderboardEntry) -> BoxFuture<'_, Result<(), BackendError>> {
+            self.inner.update_profile(entry)
+        }
+
+        fn fetch_standing<'a>(
+            &'a self,
+            board: &'a BoardId,
+            user_id: &'a str,
+            radius: usize,
+        ) -> BoxFuture<'a, Result<Option<PlayerStanding>, BackendError>> {
+            self.inner.fetch_standing(board, user_id, radius)
+        }
+
+        fn fetch_entries<'a>(
+            &'a self,
+            board: &'a BoardId,
+            user_ids: &'a [String],
+        ) -> BoxFuture<'a, Result<Vec<LeaderboardEntry>, BackendError>> {
+            self.inner.fetch_entries(board, user_ids)
+        }
+    }
+
+    fn score(user_id: &str, score: u32, submitted_at: u64) -> OutboxItem {
+        let profile = UserProfile { user_id: user_id.to_string(), ..UserProfile::default() };
+        let mut entry = LeaderboardEntry::new(&profile, GameMode::Classic, score);
+        entry.submitted_at = submitted_at;
+        OutboxItem::Score(entry)
+    }
+
+    fn scores(outbox: &Outbox) -> Vec<u32> {
+        outbox.items.iter().map(|item| item.entry().score).collect()
+    }
+
+    #[test]
+    fn push_keeps_the_best_score_per_player_mode_and_day() {
+        let mut outbox = Outbox::default();
+
+        outbox.push(score("a", 5, DAY));
+        outbox.push(score("a", 3, DAY + 60));
+        assert_eq!(scores(&outbox), [5]);
+
+        outbox.push(score("a", 8, DAY + 120));
+        assert_eq!(scores(&outbox), [8]);
+
+        outbox.push(score("a", 2, 2 * DAY));
+        outbox.push(score("b", 1, DAY));
+        assert_eq!(scores(&outbox), [8, 2, 1]);
+    }
+
+    #[test]
+    fn retries_back_off_up_to_the_limit() {
+        let mut outbox = Outbox::default();
+        outbox.push(score("a", 1, DAY));
+
+        let mut expected = RETRY_BASE_DELAY;
+        for _ in 0..12 {
+            outbox.schedule_retry();
+            let delay = outbox.retry_delay().unwrap();
+            assert!(delay <= expected && delay + Duration::from_secs(1) > expected);
+            expected = (expected * 2).min(RETRY_MAX_DELAY);
+        }
+        assert_eq!(expected, RETRY_MAX_DELAY);
+
+        outbox.retry_now();
+        assert_eq!(outbox.retry_delay(), None);
+    }
+
+    #[tokio::test]
+    async fn flush_keeps_writes_that_may_succeed_later() {
+        let backend = FailingBackend::default();
+        let mut outbox = Outbox::default();
+        outbox.push(score("a", 5, DAY));
+        outbox.push(score("b", 7, DAY));
+
+        for error in [BackendError::Offline, BackendError::Timeout, BackendError::Http(503)] {
+            backend.fail_next(error.clone());
+            let result = outbox.flush(&backend).await;
+            assert!(matches!(result, FlushResult::Failed { delivered: 0, error: e, .. } if e == error));
+            assert_eq!(scores(&outbox), [5, 7]);
+            assert!(outbox.retry_delay().is_some());
+        }
+
+        let result = outbox.flush(&backend).await;
+        assert!(matches!(result, FlushResult::Done { delivered: 2, .. }));
+        assert!(outbox.is_empty());
+        let board = backend.fetch_page(&BoardId::all_time(GameMode::Classic), None, 10).await.ok().unwrap();
+        assert_eq!(board.entries.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn flush_drops_writes_the_backend_refuses() {
+        let backend = FailingBackend::default();
+        let mut outbox = Outbox::default();
+        outbox.push(score("a", 5, DAY));
+        outbox.push(score("b", 7, DAY));
+
+        backend.fail_next(BackendError::Http(400));
+        let result = outbox.flush(&backend).await;
+        assert!(matches!(result, FlushResult::Failed { delivered: 0, error: BackendError::Http(400), .. }));
+        assert_eq!(scores(&outbox), [7]);
+
+        // Losing an ETag race or being rate limited isn't a refusal.
+        for status in [412, 429] {
+            backend.fail_next(BackendError::Http(status));
+            let result = outbox.flush(&backend).await;
+            assert!(matches!(result, FlushResult::Failed { delivered: 0, error: BackendError::Http(s), .. } if s == status));
+            assert_eq!(scores(&outbox), [7]);
+        }
+
+        let result = outbox.flush(&backend).await;
+        assert!(matches!(result, FlushResult::Done { delivered: 1, .. }));
+        let board = backend.fetch_page(&BoardId::all_time(GameMode::Classic), None, 10).await.ok().unwrap();
+        assert_eq!(board.entries.iter().map(|e| e.user_id.as_str()).collect::<Vec<_>>(), ["b"]);
+    }
+
+    #[tokio::test]
+    async fn flush_holds_writes_while_signed_out() {
+        let backend = FailingBackend::default();
+        let mut outbox = Outbox::default();
+        outbox.push(score("a", 5, DAY));
+
+        for error in [
+            BackendError::SignIn("refused".to_string()),
+            BackendError::Http(401),
+            BackendError::Http(403),
+        ] {
+            backend.fail_next(error.clone());
+            let result = outbox.flush(&backend).await;
+            assert!(matches!(result, FlushResult::Failed { delivered: 0, error: e, .. } if e == error));
+            assert_eq!(scores(&outbox), [5]);
+            assert_eq!(outbox.retry_delay(), None);
+        }
+    }
+
+    #[test]
+    fn saving_replaces_the_queue_file_whole() {
+        let dir = std::env::temp_dir().join(format!("outbox-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("outbox.json");
+
+        let mut outbox = Outbox::load(Some(path.clone()));
+        outbox.push(score("a", 5, DAY));
+        outbox.push(score("b", 7, DAY));
+        assert!(!path.with_extension("json.tmp").exists());
+        assert_eq!(scores(&Outbox::load(Some(path.clone()))), [5, 7]);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,513 @@
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use serde::Deserialize;
+
+/// Lives in the data directory, beside the save file.
+pub const CONFIG_FILE_NAME: &str = "config.json";
+pub const PROFILE_ENV_VAR: &str = "SNAKE_PROFILE";
+pub const LEADERBOARD_ENV_VAR: &str = "SNAKE_LEADERBOARD";
+pub const FIREBASE_URL_ENV_VAR: &str = "FIREBASE_URL";
+pub const FIREBASE_API_KEY_ENV_VAR: &str = "FIREBASE_API_KEY";
+pub const IDENTITY_ENV_VAR: &str = "SNAKE_IDENTITY";
+/// Token lifetime handed out by the local identity stand-in unless one is given.
+const STUB_TOKEN_LIFETIME: u64 = 3600;
+
+/// Named sets of settings, so switching between servers is a single choice.
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum Profile {
+    Dev,
+    Staging,
+    #[default]
+    Prod,
+}
+
+impl Profile {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Profile::Dev => "dev",
+            Profile::Staging => "staging",
+            Profile::Prod => "prod",
+        }
+    }
+}
+
+impl FromStr for Profile {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "dev" => Ok(Profile::Dev),
+            "staging" => Ok(Profile::Staging),
+            "prod" => Ok(Profile::Prod),
+            _ => Err(format!("unknown profile \"{}\", expected dev, staging or prod", s)),
+        }
+    }
+}
+
+#[derive(Clone)]
+enum LeaderboardKind {
+    Firebase,
+    Memory,
+    File(PathBuf),
+}
+
+#[derive(Clone)]
+enum IdentityKind {
+    /// Firebase Authentication, using the configured API key.
+    Firebase,
+    /// A [`StubIdentityServer`](crate::backend::StubIdentityServer) started in
+    /// this process.
+    Local { token_lifetime: u64 },
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum IdentitySource {
+    Firebase { api_key: String },
+    Local { token_lifetime: u64 },
+}
+
+/// Where scores go, as settled at startup.
+#[derive(Clone, Debug, PartialEq)]
+pub enum LeaderboardSource {
+    Memory,
+    File(PathBuf),
+    Firebase { url: String, identity: IdentitySource },
+    /// Nothing usable is configured. The game still runs, showing the leaderboard
+    /// as unavailable for this reason.
+    Unavailable(String),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BackendConfig {
+    pub profile: Profile,
+    pub leaderboard: LeaderboardSource,
+}
+
+pub enum ConfigError {
+    /// The config file exists but couldn't be read or parsed.
+    File { path: PathBuf, detail: String },
+    /// A setting was given a value that can't work. `setting` says where it came from.
+    Invalid { setting: String, detail: String },
+    /// A command-line argument the game doesn't know.
+    UnknownArg(String),
+    /// A release build can't do without this setting.
+    Missing { setting: &'static str, profile: Profile },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::File { path, detail } => write!(f, "couldn't read {}: {}", path.display(), detail),
+            ConfigError::Invalid { setting, detail } => write!(f, "invalid {}: {}", setting, detail),
+            ConfigError::UnknownArg(arg) => write!(
+                f,
+                "unknown option \"{}\", expected --profile, --leaderboard, --firebase-url, --firebase-api-key or --identity",
+                arg
+            ),
+            ConfigError::Missing { setting, profile } => {
+                write!(f, "no {} is configured for the {} profile", setting, profile.name())
+            }
+        }
+    }
+}
+
+/// Settings that may come from any source. Unset fields fall through to
+/// earlier sources.
+#[derive(Clone, Default)]
+struct Endpoint {
+    leaderboard: Option<LeaderboardKind>,
+    firebase_url: Option<String>,
+    firebase_api_key: Option<String>,
+    identity: Option<IdentityKind>,
+}
+
+impl Endpoint {
+    /// Settings by the names the config file uses.
+    const KEYS: [&'static str; 4] = ["leaderboard", "firebase_url", "firebase_api_key", "identity"];
+
+    /// Parses and stores one setting. `setting` names it in error messages.
+    fn set(&mut self, key: &str, value: &str, setting: &str) -> Result<(), ConfigError> {
+        let invalid = |detail: String| ConfigError::Invalid { setting: setting.to_string(), detail };
+
+        match key {
+            "leaderboard" => {
+                self.leaderboard = Some(match value {
+                    "firebase" => LeaderboardKind::Firebase,
+                    "memory" => LeaderboardKind::Memory,
+                    _ => match value.strip_prefix("file:") {
+                        Some(path) if !path.is_empty() => LeaderboardKind::File(PathBuf::from(path)),
+                        _ => {
+                            return Err(invalid(format!(
+                                "\"{}\" is not firebase, memory or file:<path>",
+                                value
+                            )))
+                        }
+                    },
+                });
+            }
+            "firebase_url" => {
+                let url = reqwest::Url::parse(value).map_err(|e| invalid(format!("\"{}\" is not a URL: {}", value, e)))?;
+                if !matches!(url.scheme(), "http" | "https") {
+                    return Err(invalid(format!("\"{}\" must use http or https", value)));
+                }
+                self.firebase_url = Some(value.to_string());
+            }
+            "firebase_api_key" => self.firebase_api_key = Some(value.to_string()),
+            "identity" => {
+                self.identity = Some(match value {
+                    "firebase" => IdentityKind::Firebase,
+                    "local" => IdentityKind::Local { token_lifetime: STUB_TOKEN_LIFETIME },
+                    _ => match value.strip_prefix("local:").map(str::parse) {
+                        Some(Ok(token_lifetime)) => IdentityKind::Local { token_lifetime },
+                        _ => {
+                            return Err(invalid(format!(
+                                "\"{}\" is not firebase, local or local:<seconds>",
+                                value
+                            )))
+                        }
+                    },
+                });
+            }
+            _ => return Err(invalid("no such setting".to_string())),
+        }
+        Ok(())
+    }
+
+    fn overlay(&mut self, other: &Endpoint) {
+        if other.leaderboard.is_some() {
+            self.leaderboard = other.leaderboard.clone();
+        }
+        if other.firebase_url.is_some() {
+            self.firebase_url = other.firebase_url.clone();
+        }
+        if other.firebase_api_key.is_some() {
+            self.firebase_api_key = other.firebase_api_key.clone();
+        }
+        if other.identity.is_some() {
+            self.identity = other.identity.clone();
+        }
+    }
+
+    /// A missing URL or API key leaves the leaderboard unavailable, except under
+    /// the prod profile, where it means the build was shipped without them.
+    fn into_source(self, profile: Profile) -> Result<LeaderboardSource, ConfigError> {
+        let missing = |setting: &'static str| {
+            let error = ConfigError::Missing { setting, profile };
+            if profile == Profile::Prod {
+                Err(error)
+            } else {
+                Ok(LeaderboardSource::Unavailable(error.to_string()))
+            }
+        };
+
+        match self.leaderboard.unwrap_or(LeaderboardKind::Firebase) {
+            LeaderboardKind::Memory => Ok(LeaderboardSource::Memory),
+            LeaderboardKind::File(path) => Ok(LeaderboardSource::File(path)),
+            LeaderboardKind::Firebase => {
+                let Some(url) = self.firebase_url else {
+                    return missing("Firebase URL");
+                };
+                let identity = match (self.identity.unwrap_or(IdentityKind::Firebase), self.firebase_api_key) {
+                    (IdentityKind::Local { token_lifetime }, _) => IdentitySource::Local { token_lifetime },
+                    (IdentityKind::Firebase, Some(api_key)) => IdentitySource::Firebase { api_key },
+                    (IdentityKind::Firebase, None) => return missing("Firebase API key"),
+                };
+                Ok(LeaderboardSource::Firebase { url, identity })
+            }
+        }
+    }
+}
+
+/// One source's worth of settings.
+#[derive(Default)]
+struct Layer {
+    profile: Option<Profile>,
+    /// Only the config file defines profiles.
+    profiles: HashMap<Profile, Endpoint>,
+    /// Applied on top of whichever profile is selected.
+    overrides: Endpoint,
+}
+
+/// The config file as written, checked setting by setting afterwards.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ConfigFile {
+    profile: Option<String>,
+    #[serde(default)]
+    profiles: HashMap<String, HashMap<String, String>>,
+}
+
+impl Layer {
+    /// A missing file is fine and sets nothing.
+    fn from_file(path: &Path) -> Result<Self, ConfigError> {
+        let file_error = |detail: String| ConfigError::File { path: path.to_path_buf(), detail };
+        let text = match std::fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(file_error(e.to_string())),
+        };
+        let file: ConfigFile = serde_json::from_str(&text).map_err(|e| file_error(e.to_string()))?;
+
+        let mut layer = Self::default();
+        if let Some(name) = file.profile {
+            layer.profile = Some(name.parse().map_err(file_error)?);
+        }
+        for (name, settings) in file.profiles {
+            let profile: Profile = name.parse().map_err(file_error)?;
+            let endpoint = layer.profiles.entry(profile).or_default();
+            for (key, value) in settings {
+                let setting = format!("{} in the {} profile of {}", key, name, path.display());
+                endpoint.set(&key, &value, &setting)?;
+            }
+        }
+        Ok(layer)
+    }
+
+    /// Empty variables count as unset.
+    fn from_env(env: &HashMap<String, OsString>) -> Result<Self, ConfigError> {
+        let vars = [
+            ("leaderboard", LEADERBOARD_ENV_VAR),
+            ("firebase_url", FIREBASE_URL_ENV_VAR),
+            ("firebase_api_key", FIREBASE_API_KEY_ENV_VAR),
+            ("identity", IDENTITY_ENV_VAR),
+        ];
+
+        let mut layer = Self::default();
+        if let Some(name) = env_var(env, PROFILE_ENV_VAR)? {
+            layer.profile = Some(name.parse().map_err(|detail| invalid(PROFILE_ENV_VAR, detail))?);
+        }
+        for (key, var) in vars {
+            if let Some(value) = env_var(env, var)? {
+                layer.overrides.set(key, &value, var)?;
+            }
+        }
+        Ok(layer)
+    }
+
+    /// Takes `--name value` or `--name=value`, where the names are the config
+    /// file's settings with dashes, plus `--profile`.
+    fn from_args(args: impl IntoIterator<Item = String>) -> Result<Self, ConfigError> {
+        let mut layer = Self::default();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            let Some(flag) = arg.strip_prefix("--") else {
+                return Err(ConfigError::UnknownArg(arg));
+            };
+            let (name, value) = match flag.split_once('=') {
+                Some((name, value)) => (name.to_string(), Some(value.to_string())),
+                None => (flag.to_string(), None),
+            };
+            let key = name.replace('-', "_");
+            if key != "profile" && !Endpoint::KEYS.contains(&key.as_str()) {
+                return Err(ConfigError::UnknownArg(arg));
+            }
+
+            let setting = format!("--{}", name);
+            let Some(value) = value.or_else(|| args.next()) else {
+                return Err(invalid(&setting, "needs a value".to_string()));
+            };
+            if key == "profile" {
+                layer.profile = Some(value.parse().map_err(|detail| invalid(&setting, detail))?);
+            } else {
+                layer.overrides.set(&key, &value, &setting)?;
+            }
+        }
+        Ok(layer)
+    }
+}
+
+fn invalid(setting: &str, detail: String) -> ConfigError {
+    ConfigError::Invalid { setting: setting.to_string(), detail }
+}
+
+fn env_var(env: &HashMap<String, OsString>, name: &str) -> Result<Option<String>, ConfigError> {
+    match env.get(name).map(|value| value.to_str()) {
+        None | Some(Some("")) => Ok(None),
+        Some(Some(value)) => Ok(Some(value.to_string())),
+        Some(None) => Err(invalid(name, "not valid UTF-8".to_string())),
+    }
+}
+
+/// The process environment, in the form [`load`] takes it.
+pub fn process_env() -> HashMap<String, OsString> {
+    std::env::vars_os()
+        .filter_map(|(name, value)| Some((name.into_string().ok()?, value)))
+        .collect()
+}
+
+/// Reads the config file at `config_path`, then the environment `env`, then
+/// `args`, each overriding the one before. The last source to name a profile
+/// picks it; its settings from the file apply first, then any settings given
+/// directly. Invalid values anywhere are an error, while missing ones only leave
+/// the leaderboard unavailable outside the prod profile.
+pub fn load(
+    config_path: Option<&Path>,
+    env: &HashMap<String, OsString>,
+    args: impl IntoIterator<Item = String>,
+) -> Result<BackendConfig, ConfigError> {
+    let file = match config_path {
+        Some(path) => Layer::from_file(path)?,
+        None => Layer::default(),
+    };
+    let layers = [file, Layer::from_env(env)?, Layer::from_args(args)?];
+
+    let profile = layers.iter().rev().find_map(|l| l.profile).unwrap_or_default();
+    let mut endpoint = Endpoint::default();
+    for layer in &layers {
+        if let Some(settings) = layer.profiles.get(&profile) {
+            endpoint.overlay(settings);
+        }
+    }
+    for layer in &layers {
+        endpoint.overlay(&layer.overrides);
+    }
+
+    Ok(BackendConfig {
+        profile,
+        leaderboard: endpoint.into_source(profile)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FILE: &str = r#"{
+        "profile": "dev",
+        "profiles": {
+            "dev": { "leaderboard": "memory" },
+            "prod": { "firebase_url": "https://file.example", "firebase_api_key": "file-key" }
+        }
+    }"#;
+
+    fn config_file(name: &str, contents: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("snake_config_{}_{}.json", name, std::process::id()));
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    fn env(vars: &[(&str, &str)]) -> HashMap<String, OsString> {
+        vars.iter().map(|(name, value)| (name.to_string(), OsString::from(value))).collect()
+    }
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    fn firebase_url(config: &BackendConfig) -> Option<&str> {
+        match &config.leaderboard {
+            LeaderboardSource::Firebase { url, .. } => Some(url),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn arguments_beat_the_environment_which_beats_the_file() {
+        let path = config_file("precedence", FILE);
+        let load = |vars: &[(&str, &str)], given: &[&str]| load(Some(&path), &env(vars), args(given)).ok().unwrap();
+
+        let config = load(&[], &[]);
+        assert_eq!(config.profile, Profile::Dev);
+        assert_eq!(config.leaderboard, LeaderboardSource::Memory);
+
+        let prod = [(PROFILE_ENV_VAR, "prod")];
+        assert_eq!(firebase_url(&load(&prod, &[])), Some("https://file.example"));
+
+        let prod_elsewhere = [(PROFILE_ENV_VAR, "prod"), (FIREBASE_URL_ENV_VAR, "https://env.example")];
+        assert_eq!(firebase_url(&load(&prod_elsewhere, &[])), Some("https://env.example"));
+
+        let config = load(&prod_elsewhere, &["--firebase-url", "https://args.example"]);
+        assert_eq!(firebase_url(&config), Some("https://args.example"));
+        assert_eq!(
+            config.leaderboard,
+            LeaderboardSource::Firebase {
+                url: "https://args.example".to_string(),
+                identity: IdentitySource::Firebase { api_key: "file-key".to_string() },
+            }
+        );
+
+        let config = load(&prod, &["--profile=dev"]);
+        assert_eq!(config.profile, Profile::Dev);
+        assert_eq!(config.leaderboard, LeaderboardSource::Memory);
+
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn unknown_profiles_are_rejected_wherever_they_appear() {
+        let named = config_file("named_profile", r#"{ "profile": "qa" }"#);
+        assert!(matches!(load(Some(&named), &env(&[]), args(&[])), Err(ConfigError::File { .. })));
+
+        let defined = config_file("defined_profile", r#"{ "profiles": { "qa": {} } }"#);
+        assert!(matches!(load(Some(&defined), &env(&[]), args(&[])), Err(ConfigError::File { .. })));
+
+        let from_env = load(None, &env(&[(PROFILE_ENV_VAR, "qa")]), args(&[]));
+        assert!(matches!(from_env, Err(ConfigError::Invalid { setting, .. }) if setting == PROFILE_ENV_VAR));
+
+        let from_args = load(None, &env(&[]), args(&["--profile", "qa"]));
+        assert!(matches!(from_args, Err(ConfigError::Invalid { setting, .. }) if setting == "--profile"));
+
+        let _ = std::fs::remove_file(named);
+        let _ = std::fs::remove_file(defined);
+    }
+
+    #[test]
+    fn invalid_values_are_errors() {
+        let dev = env(&[(PROFILE_ENV_VAR, "dev")]);
+        for given in [
+            ["--leaderboard", "sql"],
+            ["--leaderboard", "file:"],
+            ["--firebase-url", "not a url"],
+            ["--firebase-url", "ftp://example.com"],
+            ["--identity", "local:soon"],
+        ] {
+            assert!(matches!(load(None, &dev, args(&given)), Err(ConfigError::Invalid { .. })), "{:?}", given);
+        }
+
+        assert!(matches!(load(None, &dev, args(&["--firebase-url"])), Err(ConfigError::Invalid { .. })));
+        assert!(matches!(load(None, &dev, args(&["--colour", "red"])), Err(ConfigError::UnknownArg(_))));
+        assert!(matches!(
+            load(None, &env(&[(LEADERBOARD_ENV_VAR, "sql")]), args(&[])),
+            Err(ConfigError::Invalid { setting, .. }) if setting == LEADERBOARD_ENV_VAR
+        ));
+
+        let unknown_key = config_file("unknown_key", r#"{ "profiles": { "dev": { "colour": "red" } } }"#);
+        assert!(matches!(load(Some(&unknown_key), &dev, args(&[])), Err(ConfigError::Invalid { .. })));
+        let _ = std::fs::remove_file(unknown_key);
+    }
+
+    #[test]
+    fn prod_needs_a_url_and_api_key() {
+        let missing_url = load(None, &env(&[]), args(&[]));
+        assert!(matches!(missing_url, Err(ConfigError::Missing { setting: "Firebase URL", profile: Profile::Prod })));
+
+        let url_only = env(&[(FIREBASE_URL_ENV_VAR, "https://env.example")]);
+        let missing_key = load(None, &url_only, args(&[]));
+        assert!(matches!(missing_key, Err(ConfigError::Missing { setting: "Firebase API key", profile: Profile::Prod })));
+
+        // Elsewhere the game still starts, without a leaderboard.
+        let config = load(None, &url_only, args(&["--profile", "staging"])).ok().unwrap();
+        assert!(matches!(config.leaderboard, LeaderboardSource::Unavailable(_)));
+
+        let config = load(None, &url_only, args(&["--identity", "local"])).ok().unwrap();
+        assert_eq!(
+            config.leaderboard,
+            LeaderboardSource::Firebase {
+                url: "https://env.example".to_string(),
+                identity: IdentitySource::Local { token_lifetime: STUB_TOKEN_LIFETIME },
+            }
+        );
+    }
+
+    #[test]
+    fn empty_variables_count_as_unset() {
+        let vars = env(&[(PROFILE_ENV_VAR, "dev"), (LEADERBOARD_ENV_VAR, "")]);
+        let config = load(None, &vars, args(&[])).ok().unwrap();
+        assert!(matches!(config.leaderboard, LeaderboardSource::Unavailable(_)));
+    }
+}
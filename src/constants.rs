@@ -1,5 +1,20 @@
-pub const GRID_SIZE: i32 = 20;
-pub const CELL_SIZE: f32 = 15.0;
+pub const GRID_WIDTH: i32 = 20;
+pub const GRID_HEIGHT: i32 = 20;
+pub const MIN_CELL_SIZE: f32 = 8.0;
+pub const MAX_CELL_SIZE: f32 = 64.0;
+pub const UI_SCALE_RANGE: std::ops::RangeInclusive<f32> = 0.75..=2.0;
+
+pub const TICK_INTERVAL: f32 = 0.15;
+pub const RESUME_COUNTDOWN: f32 = 3.0;
+pub const SCREEN_TRANSITION_TIME: f32 = 0.2;
+pub const GAMEPAD_STICK_DEAD_ZONE: f32 = 0.5;
+
+pub const RECENT_GAMES_LIMIT: usize = 30;
+
+pub const LEADERBOARD_PAGE_SIZE: usize = 20;
+/// Entries shown either side of the player's own row.
+pub const STANDING_RADIUS: usize = 2;
+pub const PREVIOUS_WINNERS_SHOWN: usize = 3;
 
 pub const DEFAULT_SNAKE_COLOR: [u8; 3] = [0, 200, 0];
 pub const DEFAULT_BACKGROUND_COLOR: [u8; 3] = [20, 20, 20];
@@ -0,0 +1,171 @@
+use eframe::egui;
+use rand::Rng;
+use crate::events::{GameEvent, GameEventListener};
+use crate::resources::{FoodKind, Game};
+
+const BURST_PARTICLES: usize = 12;
+const BURST_LIFETIME: f32 = 0.5;
+const DEATH_LIFETIME: f32 = 1.0;
+const POPUP_LIFETIME: f32 = 0.8;
+const POPUP_RISE: f32 = 1.5;
+const SHAKE_DURATION: f32 = 0.35;
+const SHAKE_STRENGTH: f32 = 6.0;
+
+#[derive(Clone, Copy)]
+pub enum ParticleColor {
+    Food,
+    Snake,
+}
+
+/// Positions and velocities are in board cells so effects scale with the canvas.
+pub struct Particle {
+    pub pos: egui::Vec2,
+    pub velocity: egui::Vec2,
+    pub size: f32,
+    pub age: f32,
+    pub lifetime: f32,
+    pub color: ParticleColor,
+}
+
+impl Particle {
+    pub fn alpha(&self) -> f32 {
+        (1.0 - self.age / self.lifetime).clamp(0.0, 1.0)
+    }
+}
+
+pub struct Popup {
+    pub origin: egui::Vec2,
+    pub text: String,
+    pub age: f32,
+    pub color: ParticleColor,
+}
+
+impl Popup {
+    pub fn offset(&self) -> egui::Vec2 {
+        egui::vec2(0.0, -POPUP_RISE * self.age / POPUP_LIFETIME)
+    }
+
+    pub fn alpha(&self) -> f32 {
+        (1.0 - self.age / POPUP_LIFETIME).clamp(0.0, 1.0)
+    }
+}
+
+#[derive(Default)]
+pub struct Effects {
+    pub enabled: bool,
+    pub particles: Vec<Particle>,
+    pub popups: Vec<Popup>,
+    shake_time: f32,
+    death_time: Option<f32>,
+}
+
+impl Effects {
+    pub fn clear(&mut self) {
+        self.particles.clear();
+        self.popups.clear();
+        self.shake_time = 0.0;
+        self.death_time = None;
+    }
+
+    pub fn update(&mut self, dt: f32) {
+        for particle in &mut self.particles {
+            particle.age += dt;
+            particle.pos += particle.velocity * dt;
+            particle.velocity *= 1.0 - (3.0 * dt).min(1.0);
+        }
+        self.particles.retain(|p| p.age < p.lifetime);
+
+        for popup in &mut self.popups {
+            popup.age += dt;
+        }
+        self.popups.retain(|p| p.age < POPUP_LIFETIME);
+
+        self.shake_time = (self.shake_time - dt).max(0.0);
+        if let Some(time) = self.death_time.as_mut() {
+            *time += dt;
+        }
+    }
+
+    pub fn is_animating(&self) -> bool {
+        !self.particles.is_empty() || !self.popups.is_empty() || self.shake_time > 0.0
+    }
+
+    /// The snake body is drawn as scattering particles while the death animation runs.
+    pub fn hides_snake(&self) -> bool {
+        self.death_time.is_some()
+    }
+
+    pub fn shake_offset(&self) -> egui::Vec2 {
+        if self.shake_time <= 0.0 {
+            return egui::Vec2::ZERO;
+        }
+        let strength = SHAKE_STRENGTH * self.shake_time / SHAKE_DURATION;
+        let t = self.shake_time * 60.0;
+        egui::vec2(t.sin() * strength, (t * 1.3).cos() * strength)
+    }
+
+    fn burst(&mut self, cell: (i32, i32), color: ParticleColor) {
+        let mut rng = rand::thread_rng();
+        let center = cell_center(cell);
+
+        for _ in 0..BURST_PARTICLES {
+            let angle = rng.gen_range(0.0..std::f32::consts::TAU);
+            let speed = rng.gen_range(2.0..6.0);
+            self.particles.push(Particle {
+                pos: center,
+                velocity: egui::vec2(angle.cos(), angle.sin()) * speed,
+                size: rng.gen_range(0.1..0.2),
+                age: 0.0,
+                lifetime: BURST_LIFETIME,
+                color,
+            });
+        }
+    }
+
+    fn scatter_snake(&mut self, snake: &[(i32, i32)]) {
+        let mut rng = rand::thread_rng();
+
+        for &segment in snake {
+            let angle = rng.gen_range(0.0..std::f32::consts::TAU);
+            let speed = rng.gen_range(1.0..4.0);
+            self.particles.push(Particle {
+                pos: cell_center(segment),
+                velocity: egui::vec2(angle.cos(), angle.sin()) * speed,
+                size: 0.4,
+                age: 0.0,
+                lifetime: rng.gen_range(DEATH_LIFETIME * 0.6..DEATH_LIFETIME),
+                color: ParticleColor::Snake,
+            });
+        }
+        self.death_time = Some(0.0);
+    }
+}
+
+impl GameEventListener for Effects {
+    fn on_game_event(&mut self, game: &Game, event: &GameEvent) {
+        if !self.enabled {
+            return;
+        }
+
+        match *event {
+            GameEvent::AteFood { kind: FoodKind::Apple, pos } => {
+                self.burst(pos, ParticleColor::Food);
+                self.popups.push(Popup {
+                    origin: cell_center(pos),
+                    text: "+1".to_string(),
+                    age: 0.0,
+                    color: ParticleColor::Food,
+                });
+            }
+            GameEvent::Died { .. } => {
+                self.shake_time = SHAKE_DURATION;
+                self.scatter_snake(&game.snake);
+            }
+            _ => {}
+        }
+    }
+}
+
+fn cell_center(cell: (i32, i32)) -> egui::Vec2 {
+    egui::vec2(cell.0 as f32 + 0.5, cell.1 as f32 + 0.5)
+}
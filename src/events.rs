@@ -0,0 +1,14 @@
+use crate::resources::{DeathCause, Direction, FoodKind, Game};
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum GameEvent {
+    AteFood { kind: FoodKind, pos: (i32, i32) },
+    Turned { from: Direction, to: Direction },
+    Died { cause: DeathCause },
+}
+
+/// Implemented by anything that reacts to what happens inside a running game.
+/// `SnakeApp` forwards every event returned by `update_game` to each listener.
+pub trait GameEventListener {
+    fn on_game_event(&mut self, game: &Game, event: &GameEvent);
+}
@@ -0,0 +1,231 @@
+use serde::{Deserialize, Serialize};
+use crate::period;
+use crate::resources::LeaderboardEntry;
+
+/// Prefix on shared friend codes, so a scanned QR code is recognisable as one.
+const FRIEND_CODE_PREFIX: &str = "snake-friend:";
+/// User ids end up in backend paths, so anything longer or stranger is rejected.
+const MAX_USER_ID_LEN: usize = 64;
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Friend {
+    pub user_id: String,
+    /// Last name seen on a leaderboard, shown until their scores have loaded.
+    pub name: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct FriendList {
+    pub friends: Vec<Friend>,
+    /// Also keep the list on the leaderboard backend.
+    #[serde(default)]
+    pub sync: bool,
+    /// Unix seconds of the last add or remove, compared with the backed up copy
+    /// to tell which one is newer.
+    #[serde(default)]
+    pub modified_at: u64,
+}
+
+/// A friend list as kept on the leaderboard backend.
+#[derive(Serialize, Deserialize, Clone, Default, PartialEq, Debug)]
+pub struct SyncedFriends {
+    pub ids: Vec<String>,
+    #[serde(default)]
+    pub modified_at: u64,
+}
+
+/// What reconciling with the backed up list did to the local one.
+#[derive(PartialEq, Debug)]
+pub enum FriendSync {
+    /// Both already agree.
+    Unchanged,
+    /// The backed up list was newer and replaced the local one.
+    Adopted,
+    /// The local list is newer and should be backed up.
+    Push,
+}
+
+impl FriendList {
+    pub fn ids(&self) -> Vec<String> {
+        self.friends.iter().map(|f| f.user_id.clone()).collect()
+    }
+
+    pub fn contains(&self, user_id: &str) -> bool {
+        self.friends.iter().any(|f| f.user_id == user_id)
+    }
+
+    /// Adds the player behind a friend code or bare user id.
+    pub fn add(&mut self, code: &str, own_id: &str) -> Result<(), &'static str> {
+        let user_id = parse_friend_code(code).ok_or("That isn't a valid friend code")?;
+        if user_id == own_id {
+            return Err("That's your own friend code");
+        }
+        if self.contains(&user_id) {
+            return Err("Already on your friends list");
+        }
+
+        self.insert(user_id);
+        self.modified_at = period::now();
+        Ok(())
+    }
+
+    pub fn remove(&mut self, user_id: &str) {
+        self.friends.retain(|f| f.user_id != user_id);
+        self.modified_at = period::now();
+    }
+
+    pub fn synced(&self) -> SyncedFriends {
+        SyncedFriends { ids: self.ids(), modified_at: self.modified_at }
+    }
+
+    /// Reconciles with the list backed up from this or another device. The one
+    /// changed last wins outright, so removals carry over as well as additions.
+    /// Lists saved before either side kept a time are merged instead.
+    pub fn sync_with(&mut self, remote: SyncedFriends, own_id: &str) -> FriendSync {
+        let remote_ids: Vec<String> = remote
+            .ids
+            .iter()
+            .filter_map(|id| parse_friend_code(id))
+            .filter(|id| id != own_id)
+            .collect();
+        let same = remote_ids.len() == self.friends.len() && remote_ids.iter().all(|id| self.contains(id));
+
+        if remote.modified_at > self.modified_at {
+            let mut previous = std::mem::take(&mut self.friends);
+            for user_id in remote_ids {
+                match previous.iter().position(|f| f.user_id == user_id) {
+                    Some(index) => self.friends.push(previous.swap_remove(index)),
+                    None => self.insert(user_id),
+                }
+            }
+            self.modified_at = remote.modified_at;
+            return if same { FriendSync::Unchanged } else { FriendSync::Adopted };
+        }
+
+        if remote.modified_at < self.modified_at {
+            return FriendSync::Push;
+        }
+
+        if same {
+            return FriendSync::Unchanged;
+        }
+        for user_id in remote_ids {
+            if !self.contains(&user_id) {
+                self.insert(user_id);
+            }
+        }
+        self.modified_at = period::now();
+        FriendSync::Push
+    }
+
+    fn insert(&mut self, user_id: String) {
+        self.friends.push(Friend {
+            name: format!("Player {}", short_id(&user_id)),
+            user_id,
+        });
+    }
+
+    /// Picks up current names from loaded leaderboard entries.
+    pub fn update_names(&mut self, entries: &[LeaderboardEntry]) {
+        for friend in &mut self.friends {
+            if let Some(entry) = entries.iter().find(|e| e.user_id == friend.user_id) {
+                friend.name = entry.username.clone();
+            }
+        }
+    }
+}
+
+pub fn friend_code(user_id: &str) -> String {
+    format!("{}{}", FRIEND_CODE_PREFIX, user_id)
+}
+
+/// Accepts a full friend code or a bare user id, ignoring surrounding whitespace.
+pub fn parse_friend_code(code: &str) -> Option<String> {
+    let code = code.trim();
+    let user_id = code.strip_prefix(FRIEND_CODE_PREFIX).unwrap_or(code).trim();
+    let valid = !user_id.is_empty()
+        && user_id.len() <= MAX_USER_ID_LEN
+        && user_id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    valid.then(|| user_id.to_string())
+}
+
+pub fn short_id(user_id: &str) -> &str {
+    &user_id[..8.min(user_id.len())]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn list(ids: &[&str], modified_at: u64) -> FriendList {
+        let mut list = FriendList { modified_at, ..FriendList::default() };
+        for id in ids {
+            list.insert(id.to_string());
+        }
+        list
+    }
+
+    fn remote(ids: &[&str], modified_at: u64) -> SyncedFriends {
+        SyncedFriends { ids: ids.iter().map(|id| id.to_string()).collect(), modified_at }
+    }
+
+    #[test]
+    fn friend_codes_and_bare_ids_parse() {
+        assert_eq!(parse_friend_code(&friend_code("abc-123_x")), Some("abc-123_x".to_string()));
+        assert_eq!(parse_friend_code("  snake-friend: abc123 \n"), Some("abc123".to_string()));
+        assert_eq!(parse_friend_code("abc123"), Some("abc123".to_string()));
+        assert_eq!(parse_friend_code(&"a".repeat(MAX_USER_ID_LEN)), Some("a".repeat(MAX_USER_ID_LEN)));
+    }
+
+    #[test]
+    fn malformed_friend_codes_are_rejected() {
+        assert_eq!(parse_friend_code(""), None);
+        assert_eq!(parse_friend_code("   "), None);
+        assert_eq!(parse_friend_code(FRIEND_CODE_PREFIX), None);
+        assert_eq!(parse_friend_code(&"a".repeat(MAX_USER_ID_LEN + 1)), None);
+        assert_eq!(parse_friend_code("../leaderboard"), None);
+        assert_eq!(parse_friend_code("abc def"), None);
+        assert_eq!(parse_friend_code("snake-friend:abc/def"), None);
+    }
+
+    #[test]
+    fn adding_rejects_yourself_and_duplicates() {
+        let mut friends = FriendList::default();
+        assert_eq!(friends.add("me", "me"), Err("That's your own friend code"));
+        assert_eq!(friends.add(&friend_code("pal"), "me"), Ok(()));
+        assert_eq!(friends.add("pal", "me"), Err("Already on your friends list"));
+        assert_eq!(friends.ids(), ["pal"]);
+        assert!(friends.modified_at > 0);
+    }
+
+    #[test]
+    fn a_newer_backup_replaces_the_local_list() {
+        let mut friends = list(&["kept", "removed"], 10);
+        friends.friends[0].name = "Kept".to_string();
+
+        assert_eq!(friends.sync_with(remote(&["kept", "added", "me"], 20), "me"), FriendSync::Adopted);
+        assert_eq!(friends.ids(), ["kept", "added"]);
+        assert_eq!(friends.friends[0].name, "Kept");
+        assert_eq!(friends.modified_at, 20);
+
+        assert_eq!(friends.sync_with(remote(&["kept", "added"], 20), "me"), FriendSync::Unchanged);
+    }
+
+    #[test]
+    fn a_newer_local_list_is_pushed_as_is() {
+        let mut friends = list(&["kept"], 30);
+
+        assert_eq!(friends.sync_with(remote(&["kept", "removed"], 20), "me"), FriendSync::Push);
+        assert_eq!(friends.ids(), ["kept"]);
+        assert_eq!(friends.synced(), remote(&["kept"], 30));
+    }
+
+    #[test]
+    fn lists_without_times_are_merged() {
+        let mut friends = list(&["local"], 0);
+
+        assert_eq!(friends.sync_with(remote(&["remote", "local"], 0), "me"), FriendSync::Push);
+        assert_eq!(friends.ids(), ["local", "remote"]);
+        assert!(friends.modified_at > 0);
+    }
+}
@@ -1,41 +1,101 @@
 use crate::constants::*;
-use crate::resources::{Direction, Game, UserProfile};
+use crate::events::{GameEvent, GameEventListener};
+use crate::input::bindings::{capture_target, Action, KeyBindings};
+use crate::resources::{DeathCause, Direction, FoodKind, Game, Turn, UserProfile};
 use rand::Rng;
 use eframe::egui;
 
-pub fn handle_input(ctx: &egui::Context, game: &mut Game) {
-    if ctx.input(|i| i.key_pressed(egui::Key::ArrowUp) || i.key_pressed(egui::Key::W)) {
-        if game.direction != Direction::Down {
-            game.direction = Direction::Up;
-        }
-    } else if ctx.input(|i| i.key_pressed(egui::Key::ArrowDown) || i.key_pressed(egui::Key::S)) {
-        if game.direction != Direction::Up {
-            game.direction = Direction::Down;
-        }
-    } else if ctx.input(|i| i.key_pressed(egui::Key::ArrowLeft) || i.key_pressed(egui::Key::A)) {
-        if game.direction != Direction::Right {
-            game.direction = Direction::Left;
-        }
-    } else if ctx.input(|i| i.key_pressed(egui::Key::ArrowRight) || i.key_pressed(egui::Key::D)) {
-        if game.direction != Direction::Left {
-            game.direction = Direction::Right;
-        }
-    } else if ctx.input(|i| i.key_pressed(egui::Key::Space)) {
-        game.paused = !game.paused;
+pub fn handle_input(ctx: &egui::Context, game: &mut Game, bindings: &KeyBindings) {
+    if ctx.wants_keyboard_input() || capture_target(ctx).is_some() {
+        return;
+    }
+
+    if bindings.pressed(ctx, Action::Up) {
+        queue_direction(game, Direction::Up);
+    } else if bindings.pressed(ctx, Action::Down) {
+        queue_direction(game, Direction::Down);
+    } else if bindings.pressed(ctx, Action::Left) {
+        queue_direction(game, Direction::Left);
+    } else if bindings.pressed(ctx, Action::Right) {
+        queue_direction(game, Direction::Right);
+    } else if bindings.pressed(ctx, Action::TurnLeft) {
+        queue_turn(game, Turn::Left);
+    } else if bindings.pressed(ctx, Action::TurnRight) {
+        queue_turn(game, Turn::Right);
+    } else if bindings.pressed(ctx, Action::Pause) {
+        toggle_pause(game);
+    }
+}
+
+pub fn pause(game: &mut Game) {
+    if !game.game_over {
+        game.paused = true;
+        game.resume_countdown = 0.0;
+    }
+}
+
+/// Unpauses behind a short countdown so the player has time to get ready.
+pub fn resume(game: &mut Game) {
+    if game.paused {
+        game.paused = false;
+        game.resume_countdown = RESUME_COUNTDOWN;
     }
 }
 
-pub fn update_game(dt: f32, game: &mut Game, profile: &mut UserProfile) -> bool {
+/// Pressing pause during the resume countdown pauses again instead of skipping it.
+pub fn toggle_pause(game: &mut Game) {
+    if game.paused {
+        resume(game);
+    } else {
+        pause(game);
+    }
+}
+
+/// Sets the direction for the next tick. Checked against the current heading, so
+/// two quick presses can never reverse the snake into itself.
+pub fn queue_direction(game: &mut Game, direction: Direction) {
     if game.game_over || game.paused {
-        return false;
+        return;
     }
 
+    if direction != game.direction.opposite() {
+        game.pending_direction = Some(direction);
+    }
+}
+
+/// Turns relative to the snake's current heading.
+pub fn queue_turn(game: &mut Game, turn: Turn) {
+    queue_direction(game, game.direction.turned(turn));
+}
+
+pub fn update_game(dt: f32, game: &mut Game) -> Vec<GameEvent> {
+    let mut events = Vec::new();
+
+    if game.game_over || game.paused {
+        return events;
+    }
+
+    if game.resume_countdown > 0.0 {
+        game.resume_countdown = (game.resume_countdown - dt).max(0.0);
+        return events;
+    }
+
+    game.play_time += dt;
+
     game.timer += dt;
-    if game.timer < 0.15 {
-        return false;
+    if game.timer < TICK_INTERVAL {
+        return events;
     }
     game.timer = 0.0;
 
+    if let Some(next) = game.pending_direction.take() {
+        if next != game.direction {
+            events.push(GameEvent::Turned { from: game.direction, to: next });
+            game.direction = next;
+        }
+    }
+
+    let (width, height) = (GRID_WIDTH, GRID_HEIGHT);
     let head = game.snake[0];
     let mut new_head = match game.direction {
         Direction::Up => (head.0, head.1 - 1),
@@ -45,39 +105,55 @@ pub fn update_game(dt: f32, game: &mut Game, profile: &mut UserProfile) -> bool
     };
 
     if new_head.0 < 0 {
-        new_head.0 = GRID_SIZE - 1;
-    } else if new_head.0 >= GRID_SIZE {
+        new_head.0 = width - 1;
+    } else if new_head.0 >= width {
         new_head.0 = 0;
     }
     if new_head.1 < 0 {
-        new_head.1 = GRID_SIZE - 1;
-    } else if new_head.1 >= GRID_SIZE {
+        new_head.1 = height - 1;
+    } else if new_head.1 >= height {
         new_head.1 = 0;
     }
 
     if game.snake.contains(&new_head) {
-        game.game_over = true;
-        if game.score > profile.high_score {
-            profile.high_score = game.score;
-        }
-        return true;
+        end_game(game, DeathCause::SelfCollision, &mut events);
+        return events;
     }
 
     game.snake.insert(0, new_head);
 
     if new_head == game.food {
         game.score += 1;
-        let mut rng = rand::thread_rng();
-        loop {
-            let food = (rng.gen_range(0..GRID_SIZE), rng.gen_range(0..GRID_SIZE));
-            if !game.snake.contains(&food) {
-                game.food = food;
-                break;
-            }
-        }
+        events.push(GameEvent::AteFood { kind: FoodKind::Apple, pos: new_head });
+        game.food = random_free_cell(game);
     } else {
         game.snake.pop();
     }
-    
-    false
-} 
\ No newline at end of file
+
+    events
+}
+
+fn random_free_cell(game: &Game) -> (i32, i32) {
+    let (width, height) = (GRID_WIDTH, GRID_HEIGHT);
+    let mut rng = rand::thread_rng();
+    loop {
+        let cell = (rng.gen_range(0..width), rng.gen_range(0..height));
+        if !game.snake.contains(&cell) && cell != game.food {
+            return cell;
+        }
+    }
+}
+
+fn end_game(game: &mut Game, cause: DeathCause, events: &mut Vec<GameEvent>) {
+    game.game_over = true;
+    game.pending_direction = None;
+    events.push(GameEvent::Died { cause });
+}
+
+impl GameEventListener for UserProfile {
+    fn on_game_event(&mut self, game: &Game, event: &GameEvent) {
+        if let GameEvent::Died { .. } = event {
+            self.new_best = !game.resumed && self.raise_best(game.mode, game.score);
+        }
+    }
+}
@@ -0,0 +1,183 @@
+use std::collections::HashMap;
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Action {
+    Up,
+    Down,
+    Left,
+    Right,
+    TurnLeft,
+    TurnRight,
+    Pause,
+    Restart,
+    Back,
+}
+
+impl Action {
+    pub const ALL: [Action; 9] = [
+        Action::Up,
+        Action::Down,
+        Action::Left,
+        Action::Right,
+        Action::TurnLeft,
+        Action::TurnRight,
+        Action::Pause,
+        Action::Restart,
+        Action::Back,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Action::Up => "Up",
+            Action::Down => "Down",
+            Action::Left => "Left",
+            Action::Right => "Right",
+            Action::TurnLeft => "Turn left",
+            Action::TurnRight => "Turn right",
+            Action::Pause => "Pause",
+            Action::Restart => "Restart",
+            Action::Back => "Back",
+        }
+    }
+
+    fn default_keys(&self) -> Vec<egui::Key> {
+        use egui::Key;
+        match self {
+            Action::Up => vec![Key::ArrowUp, Key::W],
+            Action::Down => vec![Key::ArrowDown, Key::S],
+            Action::Left => vec![Key::ArrowLeft, Key::A],
+            Action::Right => vec![Key::ArrowRight, Key::D],
+            Action::TurnLeft => vec![Key::Q],
+            Action::TurnRight => vec![Key::E],
+            Action::Pause => vec![Key::Space, Key::P],
+            Action::Restart => vec![Key::R],
+            Action::Back => vec![Key::Escape],
+        }
+    }
+}
+
+/// Keys assigned to each action. Actions missing from a saved table fall back to
+/// their defaults, so new actions work with old save files.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KeyBindings {
+    keys: HashMap<Action, Vec<egui::Key>>,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self {
+            keys: Action::ALL.iter().map(|a| (*a, a.default_keys())).collect(),
+        }
+    }
+}
+
+impl KeyBindings {
+    pub fn keys(&self, action: Action) -> Vec<egui::Key> {
+        self.keys.get(&action).cloned().unwrap_or_else(|| action.default_keys())
+    }
+
+    pub fn pressed(&self, ctx: &egui::Context, action: Action) -> bool {
+        let keys = self.keys(action);
+        ctx.input(|i| keys.iter().any(|key| i.key_pressed(*key)))
+    }
+
+    /// Returns the action that already uses `key`, other than `action` itself.
+    pub fn conflict(&self, key: egui::Key, action: Action) -> Option<Action> {
+        Action::ALL
+            .into_iter()
+            .find(|other| *other != action && self.keys(*other).contains(&key))
+    }
+
+    pub fn bind(&mut self, action: Action, key: egui::Key) -> Result<(), Action> {
+        if let Some(other) = self.conflict(key, action) {
+            return Err(other);
+        }
+
+        let mut keys = self.keys(action);
+        if !keys.contains(&key) {
+            keys.push(key);
+        }
+        self.keys.insert(action, keys);
+        Ok(())
+    }
+
+    pub fn unbind(&mut self, action: Action, key: egui::Key) {
+        let mut keys = self.keys(action);
+        keys.retain(|k| *k != key);
+        self.keys.insert(action, keys);
+    }
+}
+
+fn capture_id() -> egui::Id {
+    egui::Id::new("key_binding_capture")
+}
+
+/// The action currently waiting for a key press in the rebinding UI. While set,
+/// global shortcuts must be ignored so the captured key is not also acted on.
+pub fn capture_target(ctx: &egui::Context) -> Option<Action> {
+    ctx.data(|d| d.get_temp(capture_id()))
+}
+
+pub fn set_capture_target(ctx: &egui::Context, action: Option<Action>) {
+    ctx.data_mut(|d| match action {
+        Some(action) => d.insert_temp(capture_id(), action),
+        None => d.remove::<Action>(capture_id()),
+    });
+}
+
+/// Returns the first key pressed this frame, if any.
+pub fn next_key_press(ctx: &egui::Context) -> Option<egui::Key> {
+    ctx.input(|i| {
+        i.events.iter().find_map(|event| match event {
+            egui::Event::Key { key, pressed: true, repeat: false, .. } => Some(*key),
+            _ => None,
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use egui::Key;
+    use super::*;
+
+    #[test]
+    fn binding_a_free_key_adds_it_alongside_the_defaults() {
+        let mut bindings = KeyBindings::default();
+
+        assert!(bindings.bind(Action::Pause, Key::Enter).is_ok());
+        assert_eq!(bindings.keys(Action::Pause), [Key::Space, Key::P, Key::Enter]);
+
+        // Binding it again changes nothing.
+        assert!(bindings.bind(Action::Pause, Key::Enter).is_ok());
+        assert_eq!(bindings.keys(Action::Pause), [Key::Space, Key::P, Key::Enter]);
+    }
+
+    #[test]
+    fn a_key_bound_elsewhere_is_refused() {
+        let mut bindings = KeyBindings::default();
+
+        assert!(bindings.conflict(Key::W, Action::Down) == Some(Action::Up));
+        assert!(bindings.conflict(Key::W, Action::Up).is_none());
+        assert!(bindings.bind(Action::Down, Key::W) == Err(Action::Up));
+        assert_eq!(bindings.keys(Action::Down), [Key::ArrowDown, Key::S]);
+        assert_eq!(bindings.keys(Action::Up), [Key::ArrowUp, Key::W]);
+
+        // Freed up, it can move over.
+        bindings.unbind(Action::Up, Key::W);
+        assert!(bindings.conflict(Key::W, Action::Down).is_none());
+        assert!(bindings.bind(Action::Down, Key::W).is_ok());
+        assert_eq!(bindings.keys(Action::Down), [Key::ArrowDown, Key::S, Key::W]);
+    }
+
+    #[test]
+    fn actions_missing_from_a_save_use_their_defaults() {
+        let bindings: KeyBindings = serde_json::from_str(r#"{ "keys": { "Pause": ["Enter"] } }"#).unwrap();
+
+        assert_eq!(bindings.keys(Action::Pause), [Key::Enter]);
+        assert_eq!(bindings.keys(Action::Back), [Key::Escape]);
+        assert!(bindings.conflict(Key::Space, Action::Back).is_none());
+    }
+}
@@ -0,0 +1,216 @@
+use std::collections::VecDeque;
+use crate::constants::GAMEPAD_STICK_DEAD_ZONE;
+use crate::resources::Direction;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum GamepadButton {
+    DPadUp,
+    DPadDown,
+    DPadLeft,
+    DPadRight,
+    Start,
+    South,
+    East,
+}
+
+/// Raw controller input as reported by a backend. Stick axes range from -1.0 to
+/// 1.0 with positive `y` pointing up.
+#[derive(Clone, Copy, PartialEq)]
+pub enum GamepadEvent {
+    ButtonPressed(GamepadButton),
+    LeftStick { x: f32, y: f32 },
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum GamepadCommand {
+    Direction(Direction),
+    Pause,
+    Confirm,
+    Back,
+}
+
+pub trait GamepadBackend {
+    /// Returns every event received since the previous call.
+    fn poll(&mut self) -> Vec<GamepadEvent>;
+}
+
+/// Used when no controller support is compiled in or the driver failed to start.
+pub struct NoGamepad;
+
+impl GamepadBackend for NoGamepad {
+    fn poll(&mut self) -> Vec<GamepadEvent> {
+        Vec::new()
+    }
+}
+
+/// Replays a fixed script, one batch of events per `poll`, so input handling can
+/// be exercised without hardware.
+pub struct MockGamepad {
+    frames: VecDeque<Vec<GamepadEvent>>,
+}
+
+impl MockGamepad {
+    pub fn new(frames: Vec<Vec<GamepadEvent>>) -> Self {
+        Self { frames: frames.into() }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.frames.is_empty()
+    }
+}
+
+impl GamepadBackend for MockGamepad {
+    fn poll(&mut self) -> Vec<GamepadEvent> {
+        self.frames.pop_front().unwrap_or_default()
+    }
+}
+
+#[cfg(gamepad_input)]
+pub struct GilrsGamepad {
+    gilrs: gilrs::Gilrs,
+    stick: (f32, f32),
+}
+
+#[cfg(gamepad_input)]
+impl GilrsGamepad {
+    pub fn new() -> Option<Self> {
+        let gilrs = gilrs::Gilrs::new().ok()?;
+        Some(Self { gilrs, stick: (0.0, 0.0) })
+    }
+}
+
+#[cfg(gamepad_input)]
+impl GamepadBackend for GilrsGamepad {
+    fn poll(&mut self) -> Vec<GamepadEvent> {
+        use gilrs::{Axis, Button, EventType};
+
+        let mut events = Vec::new();
+        let mut stick_moved = false;
+
+        while let Some(gilrs::Event { event, .. }) = self.gilrs.next_event() {
+            match event {
+                EventType::ButtonPressed(button, _) => {
+                    let mapped = match button {
+                        Button::DPadUp => GamepadButton::DPadUp,
+                        Button::DPadDown => GamepadButton::DPadDown,
+                        Button::DPadLeft => GamepadButton::DPadLeft,
+                        Button::DPadRight => GamepadButton::DPadRight,
+                        Button::Start => GamepadButton::Start,
+                        Button::South => GamepadButton::South,
+                        Button::East => GamepadButton::East,
+                        _ => continue,
+                    };
+                    events.push(GamepadEvent::ButtonPressed(mapped));
+                }
+                EventType::AxisChanged(Axis::LeftStickX, value, _) => {
+                    self.stick.0 = value;
+                    stick_moved = true;
+                }
+                EventType::AxisChanged(Axis::LeftStickY, value, _) => {
+                    self.stick.1 = value;
+                    stick_moved = true;
+                }
+                _ => {}
+            }
+        }
+
+        if stick_moved {
+            events.push(GamepadEvent::LeftStick { x: self.stick.0, y: self.stick.1 });
+        }
+
+        events
+    }
+}
+
+pub fn default_backend() -> Box<dyn GamepadBackend> {
+    #[cfg(gamepad_input)]
+    if let Some(backend) = GilrsGamepad::new() {
+        return Box::new(backend);
+    }
+
+    Box::new(NoGamepad)
+}
+
+/// Turns raw backend events into commands. The stick only produces a direction
+/// when it leaves the dead zone or changes quadrant, so holding it does not flood
+/// the turn queue.
+pub struct GamepadInput {
+    backend: Box<dyn GamepadBackend>,
+    stick_direction: Option<Direction>,
+}
+
+impl GamepadInput {
+    pub fn new(backend: Box<dyn GamepadBackend>) -> Self {
+        Self {
+            backend,
+            stick_direction: None,
+        }
+    }
+
+    pub fn poll(&mut self) -> Vec<GamepadCommand> {
+        let mut commands = Vec::new();
+
+        for event in self.backend.poll() {
+            match event {
+                GamepadEvent::ButtonPressed(button) => commands.push(match button {
+                    GamepadButton::DPadUp => GamepadCommand::Direction(Direction::Up),
+                    GamepadButton::DPadDown => GamepadCommand::Direction(Direction::Down),
+                    GamepadButton::DPadLeft => GamepadCommand::Direction(Direction::Left),
+                    GamepadButton::DPadRight => GamepadCommand::Direction(Direction::Right),
+                    GamepadButton::Start => GamepadCommand::Pause,
+                    GamepadButton::South => GamepadCommand::Confirm,
+                    GamepadButton::East => GamepadCommand::Back,
+                }),
+                GamepadEvent::LeftStick { x, y } => {
+                    let direction = stick_direction(x, y);
+                    if direction != self.stick_direction {
+                        if let Some(direction) = direction {
+                            commands.push(GamepadCommand::Direction(direction));
+                        }
+                        self.stick_direction = direction;
+                    }
+                }
+            }
+        }
+
+        commands
+    }
+}
+
+pub fn stick_direction(x: f32, y: f32) -> Option<Direction> {
+    if x.abs().max(y.abs()) < GAMEPAD_STICK_DEAD_ZONE {
+        return None;
+    }
+
+    if x.abs() > y.abs() {
+        Some(if x > 0.0 { Direction::Right } else { Direction::Left })
+    } else {
+        Some(if y > 0.0 { Direction::Up } else { Direction::Down })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stick_and_buttons_become_commands() {
+        let mock = MockGamepad::new(vec![
+            vec![GamepadEvent::LeftStick { x: 0.2, y: 0.1 }],
+            vec![GamepadEvent::LeftStick { x: 0.1, y: 0.9 }],
+            vec![GamepadEvent::LeftStick { x: 0.0, y: 0.95 }],
+            vec![GamepadEvent::ButtonPressed(GamepadButton::South)],
+            vec![GamepadEvent::LeftStick { x: -0.8, y: 0.0 }, GamepadEvent::ButtonPressed(GamepadButton::East)],
+        ]);
+        let mut input = GamepadInput::new(Box::new(mock));
+
+        // Inside the dead zone.
+        assert!(input.poll().is_empty());
+        assert!(input.poll() == [GamepadCommand::Direction(Direction::Up)]);
+        // Holding the stick in the same direction doesn't repeat.
+        assert!(input.poll().is_empty());
+        assert!(input.poll() == [GamepadCommand::Confirm]);
+        assert!(input.poll() == [GamepadCommand::Direction(Direction::Left), GamepadCommand::Back]);
+        assert!(input.poll().is_empty());
+    }
+}
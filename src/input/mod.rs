@@ -0,0 +1,4 @@
+pub mod bindings;
+pub mod gamepad;
+pub mod relative;
+pub mod swipe;
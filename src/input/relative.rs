@@ -0,0 +1,57 @@
+use eframe::egui;
+use crate::resources::Turn;
+
+/// Returns a turn when a press starts inside `rect`: the left half turns the
+/// snake left and the right half turns it right.
+pub fn tap_turn(ctx: &egui::Context, rect: egui::Rect) -> Option<Turn> {
+    let origin = ctx.input(|i| {
+        if i.pointer.primary_pressed() {
+            i.pointer.press_origin()
+        } else {
+            None
+        }
+    })?;
+
+    if !rect.contains(origin) {
+        return None;
+    }
+
+    if origin.x < rect.center().x {
+        Some(Turn::Left)
+    } else {
+        Some(Turn::Right)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::resources::{Direction, Turn};
+
+    #[test]
+    fn turns_are_relative_to_the_heading() {
+        let cases = [
+            (Direction::Up, Direction::Left, Direction::Right),
+            (Direction::Right, Direction::Up, Direction::Down),
+            (Direction::Down, Direction::Right, Direction::Left),
+            (Direction::Left, Direction::Down, Direction::Up),
+        ];
+        for (heading, left, right) in cases {
+            assert!(heading.turned(Turn::Left) == left);
+            assert!(heading.turned(Turn::Right) == right);
+        }
+    }
+
+    #[test]
+    fn opposite_turns_cancel_out_and_four_make_a_circle() {
+        for heading in [Direction::Up, Direction::Right, Direction::Down, Direction::Left] {
+            assert!(heading.turned(Turn::Left).turned(Turn::Right) == heading);
+            assert!(heading.turned(Turn::Right).turned(Turn::Right) == heading.opposite());
+
+            let mut facing = heading;
+            for _ in 0..4 {
+                facing = facing.turned(Turn::Left);
+            }
+            assert!(facing == heading);
+        }
+    }
+}
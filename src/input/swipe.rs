@@ -0,0 +1,97 @@
+use eframe::egui;
+use crate::resources::{Direction, SwipeSettings};
+
+#[derive(Clone, Copy)]
+struct SwipeAnchor {
+    pos: egui::Pos2,
+}
+
+/// Tracks a drag on `response` and returns a direction each time the pointer has
+/// travelled far and fast enough. The anchor moves to the pointer after every
+/// detected swipe, so one continuous drag can steer through several turns.
+pub fn detect(ctx: &egui::Context, response: &egui::Response, settings: &SwipeSettings) -> Option<Direction> {
+    let anchor_id = response.id.with("swipe_anchor");
+
+    if response.drag_started() {
+        if let Some(pos) = response.interact_pointer_pos() {
+            ctx.data_mut(|d| d.insert_temp(anchor_id, SwipeAnchor { pos }));
+        }
+    }
+
+    if !response.dragged() {
+        ctx.data_mut(|d| d.remove::<SwipeAnchor>(anchor_id));
+        return None;
+    }
+
+    let anchor: SwipeAnchor = ctx.data(|d| d.get_temp(anchor_id))?;
+    let pos = response.interact_pointer_pos()?;
+    let velocity = ctx.input(|i| i.pointer.velocity()).length();
+
+    let direction = classify(pos - anchor.pos, velocity, settings)?;
+    ctx.data_mut(|d| d.insert_temp(anchor_id, SwipeAnchor { pos }));
+    Some(direction)
+}
+
+/// Maps a drag displacement (in points) and pointer speed (points per second) to
+/// a direction. Drags that are too short, too slow, or too close to a diagonal
+/// are ignored.
+pub fn classify(delta: egui::Vec2, velocity: f32, settings: &SwipeSettings) -> Option<Direction> {
+    if delta.length() < settings.min_distance || velocity < settings.min_velocity {
+        return None;
+    }
+
+    let angle = delta.y.abs().atan2(delta.x.abs()).to_degrees();
+    if (angle - 45.0).abs() < settings.dead_zone_degrees {
+        return None;
+    }
+
+    let direction = if angle < 45.0 {
+        if delta.x > 0.0 { Direction::Right } else { Direction::Left }
+    } else if delta.y > 0.0 {
+        Direction::Down
+    } else {
+        Direction::Up
+    };
+    Some(direction)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FAST: f32 = 1_000.0;
+
+    fn swipe(x: f32, y: f32) -> Option<Direction> {
+        classify(egui::vec2(x, y), FAST, &SwipeSettings::default())
+    }
+
+    #[test]
+    fn straight_swipes_map_to_their_direction() {
+        assert!(swipe(80.0, 0.0) == Some(Direction::Right));
+        assert!(swipe(-80.0, 5.0) == Some(Direction::Left));
+        assert!(swipe(-5.0, 80.0) == Some(Direction::Down));
+        assert!(swipe(0.0, -80.0) == Some(Direction::Up));
+    }
+
+    #[test]
+    fn short_or_slow_drags_are_ignored() {
+        let settings = SwipeSettings::default();
+        let just_short = settings.min_distance - 0.5;
+
+        assert!(swipe(just_short, 0.0).is_none());
+        assert!(swipe(0.0, -just_short).is_none());
+        assert!(classify(egui::vec2(80.0, 0.0), settings.min_velocity - 1.0, &settings).is_none());
+        assert!(classify(egui::vec2(80.0, 0.0), settings.min_velocity, &settings) == Some(Direction::Right));
+    }
+
+    #[test]
+    fn diagonals_inside_the_dead_zone_are_ignored() {
+        assert!(swipe(60.0, 60.0).is_none());
+        assert!(swipe(-60.0, 55.0).is_none());
+        assert!(swipe(60.0, -65.0).is_none());
+
+        // 60 by 30 is about 27 degrees off the horizontal, well clear of 45 ± 10.
+        assert!(swipe(60.0, 30.0) == Some(Direction::Right));
+        assert!(swipe(-30.0, -60.0) == Some(Direction::Up));
+    }
+}
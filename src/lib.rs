@@ -1,12 +1,28 @@
+pub mod audio;
+pub mod backend;
+pub mod config;
 pub mod constants;
+pub mod effects;
+pub mod events;
+pub mod friends;
 pub mod game_logic;
+pub mod input;
+pub mod palette;
+pub mod period;
 pub mod resources;
+pub mod stats;
+pub mod theme;
 pub mod ui;
 
-use std::sync::mpsc::{Receiver, Sender};
+use std::sync::mpsc::{Receiver, RecvTimeoutError, Sender};
 use eframe::egui;
+use events::{GameEvent, GameEventListener};
+use input::bindings::{self, Action};
+use input::gamepad::{GamepadCommand, GamepadInput};
 use resources::*;
-use std::collections::HashMap;
+use backend::BoardId;
+use period::LeaderboardPeriod;
+use stats::LifetimeStats;
 use serde::{Deserialize, Serialize};
 
 #[cfg(target_os = "android")]
@@ -18,83 +34,331 @@ static ANDROID_DATA_PATH: OnceLock<std::path::PathBuf> = OnceLock::new();
 pub enum AsyncMessage {
     ProfileLoaded(UserProfile),
     LeaderboardLoaded(Leaderboard),
+    LeaderboardPageLoaded(backend::LeaderboardPage),
+    LeaderboardFailed(backend::BackendError),
     ScoreSubmitted,
+    /// The player's all-time entry for a mode as stored after a score write.
+    BestConfirmed(LeaderboardEntry),
+    /// A score submission or profile update did not reach the backend.
+    SyncFailed(backend::BackendError),
+    /// Writes still waiting in the outbox.
+    PendingUploads(usize),
+    /// Friend ids stored on the backend.
+    FriendsLoaded(friends::SyncedFriends),
 }
 
 pub enum AsyncCommand {
     LoadProfile,
-    LoadLeaderboard,
-    SubmitScore(u32, UserProfile),
+    /// First page of a board plus the standing of the given player.
+    LoadLeaderboard(String, BoardId),
+    LoadLeaderboardPage(BoardId, backend::PageCursor),
+    /// A board limited to the given players.
+    LoadFriendScores(BoardId, Vec<String>),
+    LoadFriends(String),
+    SaveFriends(String, friends::SyncedFriends),
+    SubmitScore(GameMode, u32, UserProfile),
     UpdateProfile(UserProfile),
 }
 
 #[derive(Serialize, Deserialize)]
 struct SaveState {
     profile: UserProfile,
+    #[serde(default)]
+    settings: Settings,
+    #[serde(default)]
+    stats: LifetimeStats,
+    #[serde(default)]
+    suspended_game: Option<Game>,
+    #[serde(default)]
+    friends: friends::FriendList,
 }
 
 pub struct SnakeApp {
     game: Game,
+    audio: audio::AudioSystem,
+    effects: effects::Effects,
+    gamepad: GamepadInput,
+    gamepad_commands: Vec<GamepadCommand>,
+    suspended_game: Option<Game>,
     state: GameState,
     profile: UserProfile,
+    settings: Settings,
+    stats: LifetimeStats,
+    friends: friends::FriendList,
     leaderboard: Leaderboard,
+    connection: ConnectionStatus,
+    pending_uploads: usize,
     qr_textures: QRCodeTextures,
+    themes: theme::ThemeManager,
     rx: Receiver<AsyncMessage>,
     tx: Sender<AsyncCommand>,
     last_save_time: f64,
+    window_focused: bool,
+    quitting: bool,
+    applied_ui_scale: f32,
     #[cfg(target_os = "android")]
     show_keyboard: bool,
 }
 
 impl SnakeApp {
-    pub fn new(cc: &eframe::CreationContext<'_>) -> Self {
+    pub fn new(cc: &eframe::CreationContext<'_>, config: config::BackendConfig) -> Self {
         setup_custom_fonts(&cc.egui_ctx);
 
         let (tx_to_ui, rx_from_async) = std::sync::mpsc::channel();
         let (tx_to_async, rx_from_ui) = std::sync::mpsc::channel();
 
+        // No credentials yet means this install hasn't signed a write so far.
+        let first_sign_in = matches!(config.leaderboard, config::LeaderboardSource::Firebase { .. })
+            && get_credentials_path().is_some_and(|path| !path.exists());
+
         std::thread::spawn(move || {
             let rt = tokio::runtime::Runtime::new().unwrap();
-            rt.block_on(async_loop(rx_from_ui, tx_to_ui));
+            let outbox = backend::Outbox::load(get_outbox_path());
+            rt.block_on(async_loop(rx_from_ui, tx_to_ui, backend::connect(&config, get_credentials_path()), outbox));
         });
 
-        let mut profile = UserProfile::default();
+        let mut loaded_state = None;
 
         if let Some(path) = get_save_path() {
             if let Ok(contents) = std::fs::read_to_string(&path) {
                 if let Ok(saved_state) = serde_json::from_str::<SaveState>(&contents) {
-                    profile = saved_state.profile;
+                    loaded_state = Some(saved_state);
                 }
             }
         } else if let Some(storage) = cc.storage {
-            if let Some(saved_state) = eframe::get_value::<SaveState>(storage, eframe::APP_KEY) {
-                profile = saved_state.profile;
-            }
+            loaded_state = eframe::get_value::<SaveState>(storage, eframe::APP_KEY);
+        }
+
+        let (mut profile, settings, stats, suspended_game, friends) = match loaded_state {
+            Some(saved_state) => (
+                saved_state.profile,
+                saved_state.settings,
+                saved_state.stats,
+                saved_state.suspended_game,
+                saved_state.friends,
+            ),
+            None => (
+                UserProfile::default(),
+                Settings::default(),
+                LifetimeStats::default(),
+                None,
+                friends::FriendList::default(),
+            ),
+        };
+        profile.migrate_bests();
+
+        // Players from before writes were signed belong to whichever account
+        // writes for them first, and every write claims the player before
+        // anything else, so claim this one straight away.
+        if first_sign_in && profile.high_score > 0 {
+            let _ = tx_to_async.send(AsyncCommand::UpdateProfile(profile.clone()));
         }
 
-        let _ = tx_to_async.send(AsyncCommand::LoadLeaderboard);
+        let initial_board = BoardId::all_time(GameMode::Classic);
+        let _ = tx_to_async.send(AsyncCommand::LoadLeaderboard(profile.user_id.clone(), initial_board.clone()));
+        if friends.sync {
+            let _ = tx_to_async.send(AsyncCommand::LoadFriends(profile.user_id.clone()));
+        }
 
         Self {
             game: Game::default(),
+            audio: audio::AudioSystem::new(&settings.audio),
+            effects: effects::Effects::default(),
+            gamepad: GamepadInput::new(input::gamepad::default_backend()),
+            gamepad_commands: Vec::new(),
+            suspended_game,
             state: GameState::default(),
             profile,
-            leaderboard: Leaderboard::default(),
+            settings,
+            stats,
+            friends,
+            leaderboard: Leaderboard {
+                board: initial_board,
+                loading: true,
+                ..Leaderboard::default()
+            },
+            connection: ConnectionStatus::default(),
+            pending_uploads: 0,
             qr_textures: QRCodeTextures::default(),
+            themes: theme::ThemeManager::new(&cc.egui_ctx, get_themes_dir()),
             rx: rx_from_async,
             tx: tx_to_async,
             last_save_time: 0.0,
+            window_focused: true,
+            quitting: false,
+            applied_ui_scale: 1.0,
             #[cfg(target_os = "android")]
             show_keyboard: false,
         }
     }
+
+    fn save_state(&self) -> SaveState {
+        let game_in_progress = self.state.is_open(&Screen::Playing) && !self.game.game_over;
+        let suspended_game = if game_in_progress {
+            Some(self.game.clone())
+        } else {
+            self.suspended_game.clone()
+        };
+
+        SaveState {
+            profile: self.profile.clone(),
+            settings: self.settings.clone(),
+            stats: self.stats.clone(),
+            suspended_game,
+            friends: self.friends.clone(),
+        }
+    }
+
+    /// Back closes the quit dialog, pauses a running game, or returns to the
+    /// previous screen. With no history left it asks whether to quit.
+    fn go_back(&mut self) {
+        if self.state.confirm_quit {
+            self.state.confirm_quit = false;
+            return;
+        }
+
+        if self.state.current_screen == Screen::Playing {
+            if !self.game.game_over {
+                game_logic::toggle_pause(&mut self.game);
+                return;
+            }
+            self.game = Game::default();
+            self.effects.clear();
+        }
+
+        if !self.state.back() {
+            self.state.confirm_quit = true;
+        }
+    }
+
+    /// Starts over in the same mode, counting the run being left behind.
+    fn restart_game(&mut self) {
+        self.stats.record_abandoned(&self.game);
+        self.game = self.game.restarted();
+        self.effects.clear();
+    }
+
+    /// Forwards engine events to every listener. Returns `true` when the game ended
+    /// and the save file should be written straight away.
+    fn dispatch_game_events(&mut self, events: &[GameEvent]) -> bool {
+        let mut game_ended = false;
+
+        for event in events {
+            self.profile.on_game_event(&self.game, event);
+            self.stats.on_game_event(&self.game, event);
+            self.audio.on_game_event(&self.game, event);
+            self.effects.on_game_event(&self.game, event);
+
+            if let GameEvent::Died { .. } = event {
+                game_ended = true;
+                self.suspended_game = None;
+                if !self.game.resumed {
+                    let _ = self.tx.send(AsyncCommand::SubmitScore(self.game.mode, self.game.score, self.profile.clone()));
+                }
+            }
+        }
+
+        game_ended
+    }
+
+    /// Reloads the selected board, moving on to the current day or week if it has
+    /// rolled over.
+    fn request_leaderboard(&mut self) {
+        let board = BoardId::current(self.leaderboard.board.mode, self.leaderboard.board.period, period::now());
+        self.leaderboard.loading = true;
+        self.leaderboard.loading_more = false;
+
+        let command = if self.leaderboard.friends_only {
+            let mut players = self.friends.ids();
+            players.push(self.profile.user_id.clone());
+            AsyncCommand::LoadFriendScores(board, players)
+        } else {
+            AsyncCommand::LoadLeaderboard(self.profile.user_id.clone(), board)
+        };
+        let _ = self.tx.send(command);
+    }
+
+    fn request_leaderboard_page(&mut self) {
+        if let Some(cursor) = self.leaderboard.next_page.clone() {
+            self.leaderboard.loading_more = true;
+            let _ = self.tx.send(AsyncCommand::LoadLeaderboardPage(self.leaderboard.board.clone(), cursor));
+        }
+    }
+
+    fn select_leaderboard(&mut self, period: LeaderboardPeriod, friends_only: bool) {
+        if self.leaderboard.board.period != period || self.leaderboard.friends_only != friends_only {
+            self.leaderboard = Leaderboard {
+                board: BoardId::current(self.leaderboard.board.mode, period, period::now()),
+                friends_only,
+                ..Leaderboard::default()
+            };
+            self.request_leaderboard();
+        }
+    }
+
+    /// Stores the friend list, and pushes it to the backend when syncing is on.
+    fn friends_changed(&mut self) {
+        if self.friends.sync {
+            let _ = self.tx.send(AsyncCommand::SaveFriends(self.profile.user_id.clone(), self.friends.synced()));
+        }
+        if self.leaderboard.friends_only {
+            self.request_leaderboard();
+        }
+    }
+
+    fn write_save_file(&self) {
+        if let Some(path) = get_save_path() {
+            if let Ok(json) = serde_json::to_string(&self.save_state()) {
+                let _ = std::fs::write(path, json);
+            }
+        }
+    }
 }
 
 impl eframe::App for SnakeApp {
     fn save(&mut self, storage: &mut dyn eframe::Storage) {
-        let save_data = SaveState {
-            profile: self.profile.clone(),
-        };
-        eframe::set_value(storage, eframe::APP_KEY, &save_data);
+        eframe::set_value(storage, eframe::APP_KEY, &self.save_state());
+        self.write_save_file();
+    }
+
+    /// Polls the controller before egui sees the frame's input. Outside of a running
+    /// game, directions and the confirm button are replayed as keyboard focus
+    /// navigation so every menu, including the pause and game over overlays, works
+    /// with a controller without screen-specific code.
+    fn raw_input_hook(&mut self, ctx: &egui::Context, raw_input: &mut egui::RawInput) {
+        let commands = self.gamepad.poll();
+        if commands.is_empty() {
+            return;
+        }
+
+        let steering = self.state.current_screen == Screen::Playing && !self.game.paused && !self.game.game_over;
+        if !steering {
+            let nothing_focused = ctx.memory(|m| m.focused().is_none());
+
+            for command in &commands {
+                let key = match command {
+                    GamepadCommand::Direction(_) if nothing_focused => egui::Key::Tab,
+                    GamepadCommand::Direction(Direction::Up) => egui::Key::ArrowUp,
+                    GamepadCommand::Direction(Direction::Down) => egui::Key::ArrowDown,
+                    GamepadCommand::Direction(Direction::Left) => egui::Key::ArrowLeft,
+                    GamepadCommand::Direction(Direction::Right) => egui::Key::ArrowRight,
+                    GamepadCommand::Confirm => egui::Key::Enter,
+                    _ => continue,
+                };
+
+                for pressed in [true, false] {
+                    raw_input.events.push(egui::Event::Key {
+                        key,
+                        physical_key: None,
+                        pressed,
+                        repeat: false,
+                        modifiers: egui::Modifiers::NONE,
+                    });
+                }
+            }
+        }
+
+        self.gamepad_commands.extend(commands);
     }
 
     fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
@@ -106,68 +370,219 @@ impl eframe::App for SnakeApp {
             force_save = true;
         }
 
-        if ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
-            match self.state.current_screen {
-                Screen::MainMenu => {
-                    std::process::exit(0);
-                }
-                Screen::Playing => {
-                    self.state.current_screen = Screen::MainMenu;
-                    self.game = Game::default();
+        let (focused, minimized) = ctx.input(|i| {
+            let viewport = i.viewport();
+            (viewport.focused.unwrap_or(true), viewport.minimized.unwrap_or(false))
+        });
+        if self.window_focused && !focused {
+            force_save = true;
+        }
+        self.window_focused = focused;
+
+        if (!focused || minimized) && self.state.current_screen == Screen::Playing {
+            game_logic::pause(&mut self.game);
+        }
+
+        if ctx.input(|i| i.viewport().close_requested()) && !self.quitting {
+            ctx.send_viewport_cmd(egui::ViewportCommand::CancelClose);
+            self.state.confirm_quit = true;
+        }
+
+        self.themes.update(ctx, self.settings.display.theme.as_deref(), now);
+
+        let shortcuts_enabled = !ctx.wants_keyboard_input() && bindings::capture_target(ctx).is_none();
+        let key_bindings = &self.settings.key_bindings;
+
+        if shortcuts_enabled && key_bindings.pressed(ctx, Action::Back) {
+            self.go_back();
+        } else if shortcuts_enabled
+            && self.state.current_screen == Screen::Playing
+            && key_bindings.pressed(ctx, Action::Restart)
+        {
+            self.restart_game();
+        }
+
+        for command in std::mem::take(&mut self.gamepad_commands) {
+            match command {
+                GamepadCommand::Back => self.go_back(),
+                GamepadCommand::Direction(direction) if self.state.current_screen == Screen::Playing => {
+                    game_logic::queue_direction(&mut self.game, direction);
                 }
-                _ => {
-                    self.state.current_screen = Screen::MainMenu;
+                GamepadCommand::Pause if self.state.current_screen == Screen::Playing => {
+                    game_logic::toggle_pause(&mut self.game);
                 }
+                _ => {}
             }
         }
 
         while let Ok(msg) = self.rx.try_recv() {
             match msg {
                 AsyncMessage::ProfileLoaded(p) => self.profile = p,
-                AsyncMessage::LeaderboardLoaded(l) => self.leaderboard = l,
+                AsyncMessage::LeaderboardLoaded(l) => {
+                    // Drop results for a board the player has already left.
+                    let current = &self.leaderboard;
+                    if l.board.mode == current.board.mode
+                        && l.board.period == current.board.period
+                        && l.friends_only == current.friends_only
+                    {
+                        if l.friends_only {
+                            self.friends.update_names(&l.entries);
+                        }
+                        self.leaderboard = l;
+                    }
+                    self.connection = ConnectionStatus::Online;
+                }
+                AsyncMessage::LeaderboardPageLoaded(page) => {
+                    // A refresh since the request started replaces the list wholesale.
+                    if self.leaderboard.loading_more {
+                        self.leaderboard.entries.extend(page.entries);
+                        self.leaderboard.next_page = page.next;
+                        self.leaderboard.loading_more = false;
+                    }
+                    self.connection = ConnectionStatus::Online;
+                }
+                AsyncMessage::LeaderboardFailed(err) => {
+                    self.connection = ConnectionStatus::from_error(&err);
+                    self.leaderboard.loading = false;
+                    self.leaderboard.loading_more = false;
+                    self.leaderboard.error = Some(err);
+                }
                 AsyncMessage::ScoreSubmitted => {
-                    let _ = self.tx.send(AsyncCommand::LoadLeaderboard);
+                    self.connection = ConnectionStatus::Online;
+                    self.request_leaderboard();
+                }
+                AsyncMessage::BestConfirmed(entry) => {
+                    // Another device may have posted a higher score in the meantime.
+                    if entry.user_id == self.profile.user_id {
+                        self.profile.raise_best(entry.mode, entry.score);
+                    }
+                }
+                AsyncMessage::SyncFailed(err) => {
+                    self.connection = ConnectionStatus::from_error(&err);
+                }
+                AsyncMessage::PendingUploads(count) => self.pending_uploads = count,
+                AsyncMessage::FriendsLoaded(remote) => {
+                    let own_id = self.profile.user_id.clone();
+                    match self.friends.sync_with(remote, &own_id) {
+                        friends::FriendSync::Unchanged => {}
+                        friends::FriendSync::Adopted => {
+                            force_save = true;
+                            if self.leaderboard.friends_only {
+                                self.request_leaderboard();
+                            }
+                        }
+                        friends::FriendSync::Push => {
+                            force_save = true;
+                            self.friends_changed();
+                        }
+                    }
                 }
             }
         }
 
+        let was_paused = self.game.paused;
+
         if self.state.current_screen == Screen::Playing {
-            game_logic::handle_input(ctx, &mut self.game);
+            game_logic::handle_input(ctx, &mut self.game, &self.settings.key_bindings);
             let dt = ctx.input(|i| i.stable_dt);
-            let should_submit = game_logic::update_game(dt, &mut self.game, &mut self.profile);
+            let events = game_logic::update_game(dt, &mut self.game);
 
-            if should_submit {
-                let _ = self.tx.send(AsyncCommand::SubmitScore(self.profile.high_score, self.profile.clone()));
-                ctx.request_repaint();
+            self.effects.enabled = !self.settings.reduced_motion;
+            if !self.effects.enabled {
+                self.effects.clear();
+            }
+            self.effects.update(dt);
+
+            if self.dispatch_game_events(&events) {
                 force_save = true;
             }
             ctx.request_repaint();
         }
 
+        let music_playing = self.state.current_screen == Screen::Playing
+            && !self.game.paused
+            && self.game.resume_countdown <= 0.0
+            && !self.game.game_over;
+        self.audio.set_music(music_playing, constants::TICK_INTERVAL);
+
         match self.state.current_screen {
             Screen::Playing => {
-                egui::CentralPanel::default().show(ctx, |ui| {
-                    ui::game_screen::show_game_screen(ui, &mut self.state, &mut self.game, &self.profile);
-                });
+                let action = egui::CentralPanel::default().show(ctx, |ui| {
+                    ui::game_screen::show_game_screen(
+                        ui,
+                        &mut self.state,
+                        &mut self.game,
+                        &self.profile,
+                        &mut self.effects,
+                        &self.settings,
+                        self.themes.active.as_ref(),
+                    )
+                }).inner;
+                match action {
+                    Some(ui::game_screen::GameScreenAction::Back) => self.go_back(),
+                    Some(ui::game_screen::GameScreenAction::Restart) => self.restart_game(),
+                    Some(ui::game_screen::GameScreenAction::QuitToMenu) => {
+                        self.stats.record_abandoned(&self.game);
+                        self.state.reset_to(Screen::MainMenu);
+                        self.game = Game::default();
+                        self.effects.clear();
+                    }
+                    None => {}
+                }
             }
             Screen::MainMenu => {
                 egui::CentralPanel::default().show(ctx, |ui| {
-                    ui::main_menu::show_main_menu(ui, &mut self.state, &self.profile);
+                    let continue_game = ui::main_menu::show_main_menu(
+                        ui,
+                        &mut self.state,
+                        self.connection,
+                        self.suspended_game.is_some(),
+                    );
+
+                    if continue_game {
+                        if let Some(mut game) = self.suspended_game.take() {
+                            game.resumed = true;
+                            game.paused = true;
+                            self.game = game;
+                            self.effects.clear();
+                            self.state.navigate(Screen::Playing);
+                        }
+                    } else if self.state.current_screen == Screen::Playing {
+                        if let Some(game) = self.suspended_game.take() {
+                            self.stats.record_abandoned(&game);
+                        }
+                        self.game = Game::new(GameMode::Classic);
+                        self.effects.clear();
+                    }
                 });
             }
             Screen::Leaderboard => {
                 egui::CentralPanel::default().show(ctx, |ui| {
-                    let should_refresh = ui::leaderboard::show_leaderboard_screen(ui, &mut self.state, &self.leaderboard);
-                    
-                    if should_refresh {
-                        let _ = self.tx.send(AsyncCommand::LoadLeaderboard);
+                    let action = ui::leaderboard::show_leaderboard_screen(
+                        ui,
+                        &mut self.state,
+                        &self.leaderboard,
+                        &self.profile.user_id,
+                        !self.friends.friends.is_empty(),
+                        self.connection,
+                        self.pending_uploads,
+                    );
+
+                    match action {
+                        Some(ui::leaderboard::LeaderboardAction::Refresh) => self.request_leaderboard(),
+                        Some(ui::leaderboard::LeaderboardAction::LoadMore) => self.request_leaderboard_page(),
+                        Some(ui::leaderboard::LeaderboardAction::Select { period, friends_only }) => {
+                            self.select_leaderboard(period, friends_only);
+                        }
+                        None => {}
                     }
                 });
             }
             Screen::Settings => {
                 egui::CentralPanel::default().show(ctx, |ui| {
-                    ui::settings::show_settings_screen(ui, &mut self.state);
+                    ui::settings::show_settings_screen(ui, &mut self.state, &mut self.settings, &mut self.themes);
                 });
+                self.audio.apply_settings(&self.settings.audio);
             }
             Screen::Profile => {
                 egui::CentralPanel::default().show(ctx, |ui| {
@@ -176,6 +591,7 @@ impl eframe::App for SnakeApp {
                         ui,
                         &mut self.state,
                         &mut self.profile,
+                        self.themes.active.as_ref(),
                         &mut self.show_keyboard,
                     );
 
@@ -184,6 +600,7 @@ impl eframe::App for SnakeApp {
                         ui,
                         &mut self.state,
                         &mut self.profile,
+                        self.themes.active.as_ref(),
                     );
 
                     if should_sync {
@@ -193,84 +610,231 @@ impl eframe::App for SnakeApp {
                     }
                 });
             }
+            Screen::Stats => {
+                egui::CentralPanel::default().show(ctx, |ui| {
+                    ui::stats::show_stats_screen(ui, &mut self.state, &self.stats);
+                });
+            }
             Screen::Share => {
                 egui::CentralPanel::default().show(ctx, |ui| {
                     ui::share::show_share_screen(ui, &mut self.state, &mut self.qr_textures, ctx);
                 });
             }
+            Screen::Friends => {
+                let mut changed = None;
+                egui::CentralPanel::default().show(ctx, |ui| {
+                    #[cfg(target_os = "android")]
+                    let result = ui::friends::show_friends_screen(
+                        ui,
+                        &mut self.state,
+                        &mut self.friends,
+                        &self.profile,
+                        &mut self.qr_textures,
+                        &mut self.show_keyboard,
+                    );
+
+                    #[cfg(not(target_os = "android"))]
+                    let result = ui::friends::show_friends_screen(
+                        ui,
+                        &mut self.state,
+                        &mut self.friends,
+                        &self.profile,
+                        &mut self.qr_textures,
+                    );
+
+                    changed = result;
+                });
+
+                match changed {
+                    Some(ui::friends::FriendsChange::List) => {
+                        self.friends_changed();
+                        force_save = true;
+                    }
+                    // Whichever list is newer wins once the backed up one has loaded.
+                    Some(ui::friends::FriendsChange::SyncEnabled) => {
+                        let _ = self.tx.send(AsyncCommand::LoadFriends(self.profile.user_id.clone()));
+                        force_save = true;
+                    }
+                    Some(ui::friends::FriendsChange::SyncDisabled) => force_save = true,
+                    None => {}
+                }
+            }
+        }
+
+        if self.state.confirm_quit && ui::dialogs::show_quit_dialog(ctx, &mut self.state) {
+            self.quitting = true;
+            force_save = true;
+        }
+
+        // Waiting for the pointer to be released keeps the UI from rescaling under
+        // the scale slider while it is being dragged.
+        let ui_scale = self.settings.display.ui_scale;
+        if ui_scale != self.applied_ui_scale && !ctx.input(|i| i.pointer.any_down()) {
+            ctx.set_zoom_factor(ui_scale);
+            self.applied_ui_scale = ui_scale;
+        }
+
+        let dt = ctx.input(|i| i.stable_dt);
+        if self.settings.reduced_motion {
+            self.state.transition = 1.0;
+        } else if self.state.transition < 1.0 {
+            self.state.transition = (self.state.transition + dt / constants::SCREEN_TRANSITION_TIME).min(1.0);
+            ui::paint_screen_transition(ctx, self.state.transition);
+            ctx.request_repaint();
+        }
+
+        if self.game.paused && !was_paused {
+            force_save = true;
         }
 
         if force_save {
             self.last_save_time = now;
 
             if let Some(storage) = frame.storage_mut() {
-                let save_data = SaveState {
-                    profile: self.profile.clone(),
-                };
-                eframe::set_value(storage, eframe::APP_KEY, &save_data);
+                eframe::set_value(storage, eframe::APP_KEY, &self.save_state());
             }
 
-            if let Some(path) = get_save_path() {
-                let save_data = SaveState {
-                    profile: self.profile.clone(),
-                };
-                if let Ok(json) = serde_json::to_string(&save_data) {
-                    let _ = std::fs::write(path, json);
-                }
-            }
+            self.write_save_file();
+        }
+
+        if self.quitting {
+            // The save above has already hit the disk, so closing can't lose progress.
+            #[cfg(target_os = "android")]
+            std::process::exit(0);
+
+            #[cfg(not(target_os = "android"))]
+            ctx.send_viewport_cmd(egui::ViewportCommand::Close);
         }
     }
 }
 
-async fn async_loop(rx: Receiver<AsyncCommand>, tx: Sender<AsyncMessage>) {
-    let base_url = option_env!("FIREBASE_URL").unwrap_or("ENV_NOT_FOUND");
-
-    let client = reqwest::Client::new();
+async fn async_loop(
+    rx: Receiver<AsyncCommand>,
+    tx: Sender<AsyncMessage>,
+    backend: Box<dyn backend::LeaderboardBackend>,
+    mut outbox: backend::Outbox,
+) {
+    // Anything left over from the last run goes out first.
+    flush_outbox(&mut outbox, backend.as_ref(), &tx).await;
+
+    loop {
+        let cmd = match outbox.retry_delay() {
+            Some(delay) => match rx.recv_timeout(delay) {
+                Ok(cmd) => cmd,
+                Err(RecvTimeoutError::Timeout) => {
+                    flush_outbox(&mut outbox, backend.as_ref(), &tx).await;
+                    continue;
+                }
+                Err(RecvTimeoutError::Disconnected) => break,
+            },
+            None => match rx.recv() {
+                Ok(cmd) => cmd,
+                Err(_) => break,
+            },
+        };
 
-    while let Ok(cmd) = rx.recv() {
         match cmd {
             AsyncCommand::LoadProfile => {}
-            AsyncCommand::LoadLeaderboard => {
-                let url = format!("{}leaderboard.json", base_url);
-
-                if let Ok(resp) = client.get(&url).send().await {
-                    if let Ok(text) = resp.text().await {
-                        if text == "null" {
-                            let _ = tx.send(AsyncMessage::LeaderboardLoaded(Leaderboard { entries: vec![] }));
-                        } else if let Ok(map) = serde_json::from_str::<HashMap<String, LeaderboardEntry>>(&text) {
-                            let mut entries: Vec<LeaderboardEntry> = map.into_values().collect();
-                            entries.sort_by(|a, b| b.score.cmp(&a.score));
-                            entries.truncate(10);
-                            let _ = tx.send(AsyncMessage::LeaderboardLoaded(Leaderboard { entries }));
+            AsyncCommand::LoadLeaderboard(user_id, board) => {
+                match backend.fetch_page(&board, None, constants::LEADERBOARD_PAGE_SIZE).await {
+                    Ok(page) => {
+                        let standing = backend
+                            .fetch_standing(&board, &user_id, constants::STANDING_RADIUS)
+                            .await
+                            .ok()
+                            .flatten();
+                        let previous_winners = match BoardId::previous(board.mode, board.period, period::now()) {
+                            Some(previous) => backend
+                                .fetch_page(&previous, None, constants::PREVIOUS_WINNERS_SHOWN)
+                                .await
+                                .map(|page| page.entries)
+                                .unwrap_or_default(),
+                            None => Vec::new(),
+                        };
+                        let _ = tx.send(AsyncMessage::LeaderboardLoaded(Leaderboard {
+                            board,
+                            entries: page.entries,
+                            next_page: page.next,
+                            standing,
+                            previous_winners,
+                            ..Leaderboard::default()
+                        }));
+
+                        // We're reachable again, so don't sit out the rest of the backoff.
+                        if !outbox.is_empty() {
+                            outbox.retry_now();
+                            flush_outbox(&mut outbox, backend.as_ref(), &tx).await;
                         }
                     }
+                    Err(err) => {
+                        let _ = tx.send(AsyncMessage::LeaderboardFailed(err));
+                    }
                 }
             }
-            AsyncCommand::UpdateProfile(profile) => {
-                let url = format!("{}leaderboard/{}.json", base_url, profile.user_id);
-                let entry = LeaderboardEntry {
-                    user_id: profile.user_id.clone(),
-                    username: profile.username.clone(),
-                    score: profile.high_score,
+            AsyncCommand::LoadLeaderboardPage(board, cursor) => {
+                let _ = match backend.fetch_page(&board, Some(&cursor), constants::LEADERBOARD_PAGE_SIZE).await {
+                    Ok(page) => tx.send(AsyncMessage::LeaderboardPageLoaded(page)),
+                    Err(err) => tx.send(AsyncMessage::LeaderboardFailed(err)),
                 };
-
-                let _ = client.patch(&url).json(&entry).send().await;
-                let _ = tx.send(AsyncMessage::ScoreSubmitted);
             }
-            AsyncCommand::SubmitScore(score, profile) => {
-                let url = format!("{}leaderboard/{}.json", base_url, profile.user_id);
-                let entry = LeaderboardEntry {
-                    user_id: profile.user_id.clone(),
-                    username: profile.username.clone(),
-                    score,
+            AsyncCommand::LoadFriendScores(board, players) => {
+                let _ = match backend.fetch_entries(&board, &players).await {
+                    Ok(entries) => tx.send(AsyncMessage::LeaderboardLoaded(Leaderboard {
+                        board,
+                        entries,
+                        friends_only: true,
+                        ..Leaderboard::default()
+                    })),
+                    Err(err) => tx.send(AsyncMessage::LeaderboardFailed(err)),
                 };
-                let _ = client.patch(&url).json(&entry).send().await;
-                let _ = tx.send(AsyncMessage::ScoreSubmitted);
+            }
+            AsyncCommand::LoadFriends(user_id) => {
+                let _ = match backend.fetch_friends(&user_id).await {
+                    Ok(friends) => tx.send(AsyncMessage::FriendsLoaded(friends)),
+                    Err(err) => tx.send(AsyncMessage::SyncFailed(err)),
+                };
+            }
+            AsyncCommand::SaveFriends(user_id, friends) => {
+                if let Err(err) = backend.save_friends(user_id, friends).await {
+                    let _ = tx.send(AsyncMessage::SyncFailed(err));
+                }
+            }
+            AsyncCommand::UpdateProfile(profile) => {
+                outbox.push(backend::OutboxItem::Profile(LeaderboardEntry::new(&profile, GameMode::default(), 0)));
+                outbox.retry_now();
+                flush_outbox(&mut outbox, backend.as_ref(), &tx).await;
+            }
+            AsyncCommand::SubmitScore(mode, score, profile) => {
+                outbox.push(backend::OutboxItem::Score(LeaderboardEntry::new(&profile, mode, score)));
+                outbox.retry_now();
+                flush_outbox(&mut outbox, backend.as_ref(), &tx).await;
             }
         }
     }
 }
 
+async fn flush_outbox(outbox: &mut backend::Outbox, backend: &dyn backend::LeaderboardBackend, tx: &Sender<AsyncMessage>) {
+    if outbox.is_empty() {
+        return;
+    }
+
+    let (delivered, confirmed, error) = match outbox.flush(backend).await {
+        backend::FlushResult::Done { delivered, confirmed } => (delivered, confirmed, None),
+        backend::FlushResult::Failed { delivered, confirmed, error } => (delivered, confirmed, Some(error)),
+    };
+
+    let _ = tx.send(AsyncMessage::PendingUploads(outbox.len()));
+    for entry in confirmed {
+        let _ = tx.send(AsyncMessage::BestConfirmed(entry));
+    }
+    if delivered > 0 {
+        let _ = tx.send(AsyncMessage::ScoreSubmitted);
+    }
+    if let Some(err) = error {
+        let _ = tx.send(AsyncMessage::SyncFailed(err));
+    }
+}
+
 fn setup_custom_fonts(ctx: &egui::Context) {
     let fonts = egui::FontDefinitions::default();
     ctx.set_fonts(fonts);
@@ -296,10 +860,17 @@ fn android_main(app: AndroidApp) {
         builder.with_android_app(app);
     }));
 
+    // There's nowhere to report a bad config on Android, so the leaderboard
+    // carries the reason instead.
+    let config = load_config(Vec::new()).unwrap_or_else(|err| config::BackendConfig {
+        profile: config::Profile::default(),
+        leaderboard: config::LeaderboardSource::Unavailable(err.to_string()),
+    });
+
     eframe::run_native(
         "Snake Game",
         options,
-        Box::new(|cc| Ok(Box::new(SnakeApp::new(cc)))),
+        Box::new(|cc| Ok(Box::new(SnakeApp::new(cc, config)))),
     )
     .unwrap();
 }
@@ -321,4 +892,33 @@ fn get_save_path() -> Option<std::path::PathBuf> {
         }
         None
     }
-}
\ No newline at end of file
+}
+
+/// Unsent scores and profile updates, kept beside the save file.
+fn get_outbox_path() -> Option<std::path::PathBuf> {
+    Some(get_save_path()?.with_file_name("outbox.json"))
+}
+
+/// Resolves the backend settings from the config file beside the save file,
+/// the process environment and `args`, see [`config::load`].
+pub fn load_config(args: impl IntoIterator<Item = String>) -> Result<config::BackendConfig, config::ConfigError> {
+    config::load(get_config_path().as_deref(), &config::process_env(), args)
+}
+
+fn get_config_path() -> Option<std::path::PathBuf> {
+    Some(get_save_path()?.with_file_name(config::CONFIG_FILE_NAME))
+}
+
+/// This install's leaderboard sign-in, kept beside the save file.
+fn get_credentials_path() -> Option<std::path::PathBuf> {
+    Some(get_save_path()?.with_file_name("credentials.json"))
+}
+
+/// Theme packs live in a `themes` folder next to the save file.
+fn get_themes_dir() -> Option<std::path::PathBuf> {
+    let dir = get_save_path()?.parent()?.join("themes");
+    if !dir.exists() {
+        let _ = std::fs::create_dir_all(&dir);
+    }
+    Some(dir)
+}
@@ -1,6 +1,7 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 use eframe::egui;
+use snake_game::config::LeaderboardSource;
 use snake_game::SnakeApp;
 
 #[cfg(not(target_os = "android"))]
@@ -8,6 +9,17 @@ use snake_game::SnakeApp;
 async fn main() -> eframe::Result<()> {
     let _ = dotenvy::dotenv();
 
+    let config = match snake_game::load_config(std::env::args().skip(1)) {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("snake_game: {}", err);
+            std::process::exit(2);
+        }
+    };
+    if let LeaderboardSource::Unavailable(reason) = &config.leaderboard {
+        eprintln!("snake_game: leaderboard disabled, {}", reason);
+    }
+
     let native_options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
             .with_inner_size([400.0, 800.0])
@@ -19,7 +31,7 @@ async fn main() -> eframe::Result<()> {
     eframe::run_native(
         "Snake Game",
         native_options,
-        Box::new(|cc| Ok(Box::new(SnakeApp::new(cc)))),
+        Box::new(|cc| Ok(Box::new(SnakeApp::new(cc, config)))),
     )
 }
 
@@ -0,0 +1,110 @@
+use crate::constants::{DEFAULT_APPLE_COLOR, DEFAULT_BACKGROUND_COLOR, DEFAULT_SNAKE_COLOR};
+
+/// WCAG AA minimum for large graphical objects against their background.
+const MIN_BACKGROUND_CONTRAST: f32 = 3.0;
+/// Snake and apple need either this much brightness difference or clearly
+/// different hues as a colour-blind player would see them.
+const MIN_SNAKE_APPLE_CONTRAST: f32 = 2.0;
+const MIN_SIMULATED_HUE_DISTANCE: f32 = 0.1;
+
+pub struct Palette {
+    pub name: &'static str,
+    pub snake: [u8; 3],
+    pub background: [u8; 3],
+    pub apple: [u8; 3],
+}
+
+/// Colour-blind options use the Okabe–Ito set, which stays distinct under
+/// the common forms of colour vision deficiency.
+pub const PALETTES: [Palette; 4] = [
+    Palette {
+        name: "Classic",
+        snake: DEFAULT_SNAKE_COLOR,
+        background: DEFAULT_BACKGROUND_COLOR,
+        apple: DEFAULT_APPLE_COLOR,
+    },
+    Palette {
+        name: "Red-green safe",
+        snake: [86, 180, 233],
+        background: [20, 20, 20],
+        apple: [230, 159, 0],
+    },
+    Palette {
+        name: "Blue-yellow safe",
+        snake: [0, 158, 115],
+        background: [20, 20, 20],
+        apple: [213, 94, 0],
+    },
+    Palette {
+        name: "High contrast",
+        snake: [255, 255, 255],
+        background: [0, 0, 0],
+        apple: [255, 220, 0],
+    },
+];
+
+/// Relative luminance as defined by WCAG 2.
+pub fn luminance(color: [u8; 3]) -> f32 {
+    let channel = |c: u8| {
+        let c = c as f32 / 255.0;
+        if c <= 0.03928 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    0.2126 * channel(color[0]) + 0.7152 * channel(color[1]) + 0.0722 * channel(color[2])
+}
+
+pub fn contrast_ratio(a: [u8; 3], b: [u8; 3]) -> f32 {
+    let (la, lb) = (luminance(a), luminance(b));
+    (la.max(lb) + 0.05) / (la.min(lb) + 0.05)
+}
+
+/// Black or white, whichever stands out more against `color`.
+pub fn outline_for(color: [u8; 3]) -> [u8; 3] {
+    if contrast_ratio(color, [0, 0, 0]) > contrast_ratio(color, [255, 255, 255]) {
+        [0, 0, 0]
+    } else {
+        [255, 255, 255]
+    }
+}
+
+/// Approximates how a deuteranope sees `color` (Machado et al. 2009, full severity).
+fn simulate_deuteranopia(color: [u8; 3]) -> [f32; 3] {
+    let [r, g, b] = color.map(|c| c as f32);
+    [
+        0.367 * r + 0.861 * g - 0.228 * b,
+        0.280 * r + 0.673 * g + 0.047 * b,
+        -0.012 * r + 0.043 * g + 0.969 * b,
+    ]
+    .map(|c| c.max(0.0))
+}
+
+/// Distance between the chromaticities of two colours, ignoring brightness.
+fn hue_distance(a: [f32; 3], b: [f32; 3]) -> f32 {
+    let normalize = |c: [f32; 3]| {
+        let sum = c.iter().sum::<f32>().max(1.0);
+        c.map(|v| v / sum)
+    };
+    let (a, b) = (normalize(a), normalize(b));
+    a.iter().zip(b).map(|(x, y)| (x - y).powi(2)).sum::<f32>().sqrt()
+}
+
+/// Human-readable problems with a colour combination, empty when it is fine.
+pub fn contrast_warnings(snake: [u8; 3], apple: [u8; 3], background: [u8; 3]) -> Vec<&'static str> {
+    let mut warnings = Vec::new();
+
+    if contrast_ratio(snake, background) < MIN_BACKGROUND_CONTRAST {
+        warnings.push("The snake is hard to see against the background");
+    }
+    if contrast_ratio(apple, background) < MIN_BACKGROUND_CONTRAST {
+        warnings.push("The apple is hard to see against the background");
+    }
+    let hues_alike = hue_distance(simulate_deuteranopia(snake), simulate_deuteranopia(apple)) < MIN_SIMULATED_HUE_DISTANCE;
+    if hues_alike && contrast_ratio(snake, apple) < MIN_SNAKE_APPLE_CONTRAST {
+        warnings.push("The snake and apple may look alike to colour-blind players");
+    }
+
+    warnings
+}
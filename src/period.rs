@@ -0,0 +1,198 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+use serde::{Deserialize, Serialize};
+
+const SECONDS_PER_DAY: u64 = 86_400;
+const DAYS_PER_WEEK: u64 = 7;
+/// 1970-01-01 was a Thursday; weeks start on Monday as in ISO 8601.
+const EPOCH_WEEKDAY: u64 = 3;
+
+/// Which slice of time a leaderboard covers. Periods roll over at midnight UTC,
+/// weeks on Monday, so every player sees the same reset.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum LeaderboardPeriod {
+    Daily,
+    Weekly,
+    #[default]
+    AllTime,
+}
+
+impl LeaderboardPeriod {
+    pub const ALL: [LeaderboardPeriod; 3] = [LeaderboardPeriod::Daily, LeaderboardPeriod::Weekly, LeaderboardPeriod::AllTime];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            LeaderboardPeriod::Daily => "Today",
+            LeaderboardPeriod::Weekly => "This Week",
+            LeaderboardPeriod::AllTime => "All Time",
+        }
+    }
+
+    pub fn previous_label(&self) -> &'static str {
+        match self {
+            LeaderboardPeriod::Daily => "Yesterday",
+            LeaderboardPeriod::Weekly => "Last Week",
+            LeaderboardPeriod::AllTime => "All Time",
+        }
+    }
+
+    /// Key of the bucket containing `time`, such as `2026-10-19` or `2026-W42`.
+    /// Empty for all-time, which has a single bucket.
+    pub fn bucket(&self, time: u64) -> String {
+        let days = (time / SECONDS_PER_DAY) as i64;
+        match self {
+            LeaderboardPeriod::Daily => {
+                let (year, month, day) = civil_from_days(days);
+                format!("{:04}-{:02}-{:02}", year, month, day)
+            }
+            LeaderboardPeriod::Weekly => {
+                let (year, week) = iso_week(days);
+                format!("{:04}-W{:02}", year, week)
+            }
+            LeaderboardPeriod::AllTime => String::new(),
+        }
+    }
+
+    /// Key of the bucket before the one containing `time`.
+    pub fn previous_bucket(&self, time: u64) -> Option<String> {
+        let length = self.length()?;
+        Some(self.bucket(time.saturating_sub(length)))
+    }
+
+    /// When the bucket containing `time` ends.
+    pub fn next_reset(&self, time: u64) -> Option<u64> {
+        let days = time / SECONDS_PER_DAY;
+        let next_start = match self {
+            LeaderboardPeriod::Daily => days + 1,
+            LeaderboardPeriod::Weekly => days + DAYS_PER_WEEK - (days + EPOCH_WEEKDAY) % DAYS_PER_WEEK,
+            LeaderboardPeriod::AllTime => return None,
+        };
+        Some(next_start * SECONDS_PER_DAY)
+    }
+
+    fn length(&self) -> Option<u64> {
+        match self {
+            LeaderboardPeriod::Daily => Some(SECONDS_PER_DAY),
+            LeaderboardPeriod::Weekly => Some(SECONDS_PER_DAY * DAYS_PER_WEEK),
+            LeaderboardPeriod::AllTime => None,
+        }
+    }
+}
+
+/// Seconds since the Unix epoch.
+pub fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+/// Short countdown such as `2d 4h`, `5h 12m` or `42s`.
+pub fn format_countdown(seconds: u64) -> String {
+    let (days, hours, minutes) = (seconds / 86_400, seconds / 3_600 % 24, seconds / 60 % 60);
+    if days > 0 {
+        format!("{}d {}h", days, hours)
+    } else if hours > 0 {
+        format!("{}h {}m", hours, minutes)
+    } else if minutes > 0 {
+        format!("{}m {}s", minutes, seconds % 60)
+    } else {
+        format!("{}s", seconds)
+    }
+}
+
+/// Gregorian (year, month, day) for a count of days since 1970-01-01, after
+/// Howard Hinnant's `civil_from_days`.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+/// Inverse of [`civil_from_days`].
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year.rem_euclid(400);
+    let month = month as i64;
+    let doy = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+/// ISO 8601 (week-year, week). The week belongs to the year its Thursday falls in.
+fn iso_week(days: i64) -> (i64, u32) {
+    let weekday = (days + EPOCH_WEEKDAY as i64).rem_euclid(DAYS_PER_WEEK as i64);
+    let thursday = days - weekday + 3;
+    let (year, _, _) = civil_from_days(thursday);
+    let week = (thursday - days_from_civil(year, 1, 1)) / DAYS_PER_WEEK as i64 + 1;
+    (year, week as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(year: i64, month: u32, day: u32) -> u64 {
+        days_from_civil(year, month, day) as u64 * SECONDS_PER_DAY
+    }
+
+    #[test]
+    fn weeks_around_new_year_follow_iso_8601() {
+        let week = |year, month, day| LeaderboardPeriod::Weekly.bucket(at(year, month, day));
+
+        assert_eq!(week(2019, 12, 30), "2020-W01");
+        assert_eq!(week(2020, 12, 31), "2020-W53");
+        assert_eq!(week(2021, 1, 3), "2020-W53");
+        assert_eq!(week(2021, 1, 4), "2021-W01");
+        assert_eq!(week(2026, 1, 1), "2026-W01");
+        assert_eq!(week(2027, 1, 1), "2026-W53");
+    }
+
+    #[test]
+    fn days_turn_over_at_midnight_utc() {
+        let midnight = at(2024, 3, 1);
+        assert_eq!(LeaderboardPeriod::Daily.bucket(midnight - 1), "2024-02-29");
+        assert_eq!(LeaderboardPeriod::Daily.bucket(midnight), "2024-03-01");
+        assert_eq!(LeaderboardPeriod::Daily.previous_bucket(midnight).as_deref(), Some("2024-02-29"));
+
+        assert_eq!(LeaderboardPeriod::Daily.next_reset(midnight - 1), Some(midnight));
+        assert_eq!(LeaderboardPeriod::Daily.next_reset(midnight), Some(midnight + SECONDS_PER_DAY));
+    }
+
+    #[test]
+    fn weeks_turn_over_at_midnight_utc_on_monday() {
+        // 2021-01-04 was a Monday.
+        let monday = at(2021, 1, 4);
+        assert_eq!(LeaderboardPeriod::Weekly.bucket(monday - 1), "2020-W53");
+        assert_eq!(LeaderboardPeriod::Weekly.bucket(monday), "2021-W01");
+        assert_eq!(LeaderboardPeriod::Weekly.previous_bucket(monday).as_deref(), Some("2020-W53"));
+
+        let next_monday = monday + DAYS_PER_WEEK * SECONDS_PER_DAY;
+        assert_eq!(LeaderboardPeriod::Weekly.next_reset(monday - 1), Some(monday));
+        assert_eq!(LeaderboardPeriod::Weekly.next_reset(monday), Some(next_monday));
+        assert_eq!(LeaderboardPeriod::Weekly.next_reset(next_monday - 1), Some(next_monday));
+    }
+
+    #[test]
+    fn all_time_never_resets() {
+        assert_eq!(LeaderboardPeriod::AllTime.bucket(at(2024, 3, 1)), "");
+        assert_eq!(LeaderboardPeriod::AllTime.previous_bucket(at(2024, 3, 1)), None);
+        assert_eq!(LeaderboardPeriod::AllTime.next_reset(at(2024, 3, 1)), None);
+    }
+
+    #[test]
+    fn countdowns_show_the_two_largest_units() {
+        assert_eq!(format_countdown(0), "0s");
+        assert_eq!(format_countdown(59), "59s");
+        assert_eq!(format_countdown(60), "1m 0s");
+        assert_eq!(format_countdown(3_599), "59m 59s");
+        assert_eq!(format_countdown(3_600), "1h 0m");
+        assert_eq!(format_countdown(86_399), "23h 59m");
+        assert_eq!(format_countdown(86_400), "1d 0h");
+        assert_eq!(format_countdown(2 * 86_400 + 4 * 3_600 + 59), "2d 4h");
+    }
+}
@@ -1,5 +1,9 @@
+use std::collections::HashMap;
 use serde::{Deserialize, Serialize};
+use crate::backend::{BackendError, BoardId, PageCursor, PlayerStanding};
+use crate::period;
 use crate::constants::*;
+use crate::input::bindings::KeyBindings;
 use eframe::egui;
 
 #[derive(Default, PartialEq, Clone, Serialize, Deserialize)]
@@ -11,6 +15,8 @@ pub enum Screen {
     Leaderboard,
     Profile,
     Share,
+    Stats,
+    Friends,
 }
 
 #[derive(PartialEq, Clone, Copy, Serialize, Deserialize)]
@@ -21,33 +27,303 @@ pub enum Direction {
     Right,
 }
 
+#[derive(PartialEq, Clone, Copy, Serialize, Deserialize)]
+pub enum Turn {
+    Left,
+    Right,
+}
+
+impl Direction {
+    pub fn opposite(&self) -> Direction {
+        match self {
+            Direction::Up => Direction::Down,
+            Direction::Down => Direction::Up,
+            Direction::Left => Direction::Right,
+            Direction::Right => Direction::Left,
+        }
+    }
+
+    pub fn turned(&self, turn: Turn) -> Direction {
+        match (self, turn) {
+            (Direction::Up, Turn::Left) | (Direction::Down, Turn::Right) => Direction::Left,
+            (Direction::Up, Turn::Right) | (Direction::Down, Turn::Left) => Direction::Right,
+            (Direction::Left, Turn::Left) | (Direction::Right, Turn::Right) => Direction::Down,
+            (Direction::Left, Turn::Right) | (Direction::Right, Turn::Left) => Direction::Up,
+        }
+    }
+}
+
+#[derive(Default, PartialEq, Eq, Hash, Clone, Copy, Debug, Serialize, Deserialize)]
+pub enum GameMode {
+    #[default]
+    Classic,
+}
+
+impl GameMode {
+    pub const ALL: [GameMode; 1] = [GameMode::Classic];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            GameMode::Classic => "Classic",
+        }
+    }
+
+    /// Stable key for per-mode bests and leaderboards.
+    pub fn id(&self) -> &'static str {
+        match self {
+            GameMode::Classic => "classic",
+        }
+    }
+}
+
+#[derive(Default, PartialEq, Clone, Copy, Serialize, Deserialize)]
+pub enum SnakeSkin {
+    #[default]
+    Classic,
+    Rounded,
+    Gradient,
+    Striped,
+    PixelArt,
+}
+
+impl SnakeSkin {
+    pub const ALL: [SnakeSkin; 5] = [
+        SnakeSkin::Classic,
+        SnakeSkin::Rounded,
+        SnakeSkin::Gradient,
+        SnakeSkin::Striped,
+        SnakeSkin::PixelArt,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            SnakeSkin::Classic => "Classic",
+            SnakeSkin::Rounded => "Rounded",
+            SnakeSkin::Gradient => "Gradient",
+            SnakeSkin::Striped => "Striped",
+            SnakeSkin::PixelArt => "Pixel Art",
+        }
+    }
+}
+
+#[derive(PartialEq, Clone, Copy, Serialize, Deserialize)]
+pub enum DeathCause {
+    SelfCollision,
+}
+
+#[derive(PartialEq, Clone, Copy, Serialize, Deserialize)]
+pub enum FoodKind {
+    Apple,
+}
+
+/// Screen router. Screens push onto `history` when navigating so Back always
+/// returns to wherever the player came from.
 #[derive(Default, Serialize, Deserialize)]
 pub struct GameState {
     pub current_screen: Screen,
+    pub history: Vec<Screen>,
+    /// Progress of the fade-in after a screen change, from 0.0 to 1.0.
+    #[serde(skip)]
+    pub transition: f32,
+    #[serde(skip)]
+    pub confirm_quit: bool,
+}
+
+impl GameState {
+    pub fn navigate(&mut self, screen: Screen) {
+        if screen != self.current_screen {
+            let previous = std::mem::replace(&mut self.current_screen, screen);
+            self.history.push(previous);
+            self.transition = 0.0;
+        }
+    }
+
+    /// Returns `false` when there is nothing left to go back to.
+    pub fn back(&mut self) -> bool {
+        match self.history.pop() {
+            Some(screen) => {
+                self.current_screen = screen;
+                self.transition = 0.0;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn reset_to(&mut self, screen: Screen) {
+        self.history.clear();
+        self.current_screen = screen;
+        self.transition = 0.0;
+    }
+
+    pub fn is_open(&self, screen: &Screen) -> bool {
+        self.current_screen == *screen || self.history.contains(screen)
+    }
 }
 
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AudioSettings {
+    pub muted: bool,
+    pub sfx_volume: f32,
+    pub music_enabled: bool,
+    pub music_volume: f32,
+}
+
+impl Default for AudioSettings {
+    fn default() -> Self {
+        Self {
+            muted: false,
+            sfx_volume: 0.8,
+            music_enabled: true,
+            music_volume: 0.4,
+        }
+    }
+}
+
+#[derive(Default, PartialEq, Clone, Copy, Serialize, Deserialize)]
+pub enum ControlMode {
+    #[default]
+    DPad,
+    Swipe,
+    Both,
+    Relative,
+}
+
+impl ControlMode {
+    pub const ALL: [ControlMode; 4] = [
+        ControlMode::DPad,
+        ControlMode::Swipe,
+        ControlMode::Both,
+        ControlMode::Relative,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ControlMode::DPad => "D-pad",
+            ControlMode::Swipe => "Swipe",
+            ControlMode::Both => "Both",
+            ControlMode::Relative => "Turn L/R",
+        }
+    }
+
+    pub fn shows_dpad(&self) -> bool {
+        matches!(self, ControlMode::DPad | ControlMode::Both)
+    }
+
+    pub fn uses_swipe(&self) -> bool {
+        matches!(self, ControlMode::Swipe | ControlMode::Both)
+    }
+
+    pub fn uses_relative(&self) -> bool {
+        *self == ControlMode::Relative
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SwipeSettings {
+    pub min_distance: f32,
+    pub min_velocity: f32,
+    pub dead_zone_degrees: f32,
+}
+
+impl Default for SwipeSettings {
+    fn default() -> Self {
+        Self {
+            min_distance: 30.0,
+            min_velocity: 200.0,
+            dead_zone_degrees: 10.0,
+        }
+    }
+}
+
+#[derive(Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ControlSettings {
+    pub mode: ControlMode,
+    pub swipe: SwipeSettings,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DisplaySettings {
+    pub ui_scale: f32,
+    /// Id of the selected theme pack, `None` for the built-in look.
+    pub theme: Option<String>,
+    /// Outlines the snake and board and draws the apple as a diamond.
+    pub high_contrast: bool,
+}
+
+impl Default for DisplaySettings {
+    fn default() -> Self {
+        Self {
+            ui_scale: 1.0,
+            theme: None,
+            high_contrast: false,
+        }
+    }
+}
+
+#[derive(Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub display: DisplaySettings,
+    pub audio: AudioSettings,
+    pub reduced_motion: bool,
+    pub controls: ControlSettings,
+    pub key_bindings: KeyBindings,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Game {
     pub snake: Vec<(i32, i32)>,
     pub direction: Direction,
+    /// Applied on the next tick. A later press before then replaces it.
+    pub pending_direction: Option<Direction>,
     pub food: (i32, i32),
     pub score: u32,
     pub game_over: bool,
     pub paused: bool,
     pub timer: f32,
+    pub mode: GameMode,
+    pub play_time: f32,
+    pub resumed: bool,
+    pub resume_countdown: f32,
 }
 
-impl Default for Game {
-    fn default() -> Self {
+impl Game {
+    pub fn new(mode: GameMode) -> Self {
+        let (width, height) = (GRID_WIDTH, GRID_HEIGHT);
+        let (cx, cy) = (width / 2, height / 2);
+
         Self {
-            snake: vec![(10, 10), (10, 11), (10, 12)],
+            snake: vec![(cx, cy), (cx, cy + 1), (cx, cy + 2)],
             direction: Direction::Up,
-            food: (5, 5),
+            pending_direction: None,
+            food: (width / 4, height / 4),
             score: 0,
             game_over: false,
             paused: false,
             timer: 0.0,
+            mode,
+            play_time: 0.0,
+            resumed: false,
+            resume_countdown: 0.0,
         }
     }
+
+    /// A fresh game in the same mode.
+    pub fn restarted(&self) -> Self {
+        Self::new(self.mode)
+    }
+}
+
+impl Default for Game {
+    fn default() -> Self {
+        Self::new(GameMode::Classic)
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -57,7 +333,41 @@ pub struct UserProfile {
     pub snake_color: [u8; 3],
     pub background_color: [u8; 3],
     pub apple_color: [u8; 3],
+    /// Best in any mode.
     pub high_score: u32,
+    #[serde(default)]
+    pub skin: SnakeSkin,
+    /// Best score per [`GameMode::id`].
+    #[serde(default)]
+    pub bests: HashMap<String, u32>,
+    /// Whether the last finished game set a new best for its mode.
+    #[serde(skip)]
+    pub new_best: bool,
+}
+
+impl UserProfile {
+    pub fn best(&self, mode: GameMode) -> u32 {
+        self.bests.get(mode.id()).copied().unwrap_or(0)
+    }
+
+    /// Records `score` as the best for `mode` if it beats the current one.
+    /// Returns whether it did.
+    pub fn raise_best(&mut self, mode: GameMode, score: u32) -> bool {
+        if score <= self.best(mode) {
+            return false;
+        }
+        self.bests.insert(mode.id().to_string(), score);
+        self.high_score = self.high_score.max(score);
+        true
+    }
+
+    /// Saves from before per-mode bests only have `high_score`, which was set
+    /// playing Classic.
+    pub fn migrate_bests(&mut self) {
+        if self.bests.is_empty() && self.high_score > 0 {
+            self.bests.insert(GameMode::Classic.id().to_string(), self.high_score);
+        }
+    }
 }
 
 impl Default for UserProfile {
@@ -72,13 +382,67 @@ impl Default for UserProfile {
             background_color: DEFAULT_BACKGROUND_COLOR,
             apple_color: DEFAULT_APPLE_COLOR,
             high_score: 0,
+            skin: SnakeSkin::default(),
+            bests: HashMap::new(),
+            new_best: false,
         }
     }
 }
 
 #[derive(Serialize, Deserialize, Default, Clone)]
 pub struct Leaderboard {
+    /// The board being shown; its period is the selected tab.
+    pub board: BoardId,
+    /// Every page loaded so far, best first.
     pub entries: Vec<LeaderboardEntry>,
+    /// Where the next page starts, `None` once everything has been loaded.
+    pub next_page: Option<PageCursor>,
+    /// Where the local player sits overall, which may be outside `entries`.
+    pub standing: Option<PlayerStanding>,
+    /// Only the player and their friends; no paging or standing.
+    pub friends_only: bool,
+    /// Top of the previous period's board, empty for all-time.
+    pub previous_winners: Vec<LeaderboardEntry>,
+    #[serde(skip)]
+    pub loading: bool,
+    #[serde(skip)]
+    pub loading_more: bool,
+    /// Why the last refresh failed. Entries from the previous success are kept.
+    #[serde(skip)]
+    pub error: Option<BackendError>,
+}
+
+#[derive(Default, Clone, Copy, PartialEq)]
+pub enum ConnectionStatus {
+    #[default]
+    Unknown,
+    Online,
+    Offline,
+    ServerError,
+    Misconfigured,
+}
+
+impl ConnectionStatus {
+    pub fn from_error(err: &BackendError) -> Self {
+        match err {
+            BackendError::Offline | BackendError::Timeout => ConnectionStatus::Offline,
+            BackendError::Misconfigured(_) => ConnectionStatus::Misconfigured,
+            BackendError::Http(_)
+            | BackendError::Parse(_)
+            | BackendError::Storage(_)
+            | BackendError::SignIn(_) => ConnectionStatus::ServerError,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ConnectionStatus::Unknown => "Connecting…",
+            ConnectionStatus::Online => "Online",
+            ConnectionStatus::Offline => "Offline",
+            ConnectionStatus::ServerError => "Server problem",
+            ConnectionStatus::Misconfigured => "Leaderboard unavailable",
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -86,12 +450,40 @@ pub struct LeaderboardEntry {
     pub user_id: String,
     pub username: String,
     pub score: u32,
+    #[serde(default)]
+    pub mode: GameMode,
+    #[serde(default)]
+    pub skin: SnakeSkin,
+    #[serde(default = "default_snake_color")]
+    pub snake_color: [u8; 3],
+    /// Unix seconds; decides which daily and weekly boards the score counts towards.
+    #[serde(default)]
+    pub submitted_at: u64,
+}
+
+impl LeaderboardEntry {
+    pub fn new(profile: &UserProfile, mode: GameMode, score: u32) -> Self {
+        Self {
+            user_id: profile.user_id.clone(),
+            username: profile.username.clone(),
+            score,
+            mode,
+            skin: profile.skin,
+            snake_color: profile.snake_color,
+            submitted_at: period::now(),
+        }
+    }
+}
+
+fn default_snake_color() -> [u8; 3] {
+    DEFAULT_SNAKE_COLOR
 }
 
 #[derive(Default)]
 pub struct QRCodeTextures {
     pub android_qr: Option<egui::TextureHandle>,
     pub ios_qr: Option<egui::TextureHandle>,
+    pub friend_qr: Option<egui::TextureHandle>,
 }
 
 pub fn generate_random_id() -> String {
@@ -0,0 +1,145 @@
+use serde::{Deserialize, Serialize};
+use crate::constants::RECENT_GAMES_LIMIT;
+use crate::events::{GameEvent, GameEventListener};
+use crate::resources::{DeathCause, Game, GameMode};
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct GameRecord {
+    pub score: u32,
+    pub mode: GameMode,
+}
+
+#[derive(Serialize, Deserialize, Default, Clone)]
+#[serde(default)]
+pub struct LifetimeStats {
+    pub games_played: u32,
+    pub total_score: u64,
+    pub total_play_time: f64,
+    pub longest_snake: u32,
+    pub self_collision_deaths: u32,
+    pub recent_games: Vec<GameRecord>,
+}
+
+impl LifetimeStats {
+    pub fn record_death(&mut self, game: &Game, cause: DeathCause) {
+        match cause {
+            DeathCause::SelfCollision => self.self_collision_deaths += 1,
+        }
+        self.record_game(game);
+    }
+
+    /// Counts a run the player left before it ended, e.g. through Restart or
+    /// Quit to Menu. Finished runs were already counted when they died, and
+    /// runs that never started are ignored.
+    pub fn record_abandoned(&mut self, game: &Game) {
+        if game.game_over || game.play_time <= 0.0 {
+            return;
+        }
+        self.record_game(game);
+    }
+
+    fn record_game(&mut self, game: &Game) {
+        self.games_played += 1;
+        self.total_score += game.score as u64;
+        self.total_play_time += game.play_time as f64;
+        self.longest_snake = self.longest_snake.max(game.snake.len() as u32);
+
+        self.recent_games.push(GameRecord {
+            score: game.score,
+            mode: game.mode,
+        });
+        if self.recent_games.len() > RECENT_GAMES_LIMIT {
+            let excess = self.recent_games.len() - RECENT_GAMES_LIMIT;
+            self.recent_games.drain(..excess);
+        }
+    }
+
+    pub fn average_score(&self) -> f32 {
+        if self.games_played == 0 {
+            return 0.0;
+        }
+        self.total_score as f32 / self.games_played as f32
+    }
+}
+
+impl GameEventListener for LifetimeStats {
+    fn on_game_event(&mut self, game: &Game, event: &GameEvent) {
+        if let GameEvent::Died { cause } = event {
+            self.record_death(game, *cause);
+        }
+    }
+}
+
+pub fn format_play_time(seconds: f64) -> String {
+    let total = seconds as u64;
+    let hours = total / 3600;
+    let minutes = (total % 3600) / 60;
+    let secs = total % 60;
+
+    if hours > 0 {
+        format!("{}h {:02}m", hours, minutes)
+    } else {
+        format!("{}m {:02}s", minutes, secs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn played(score: u32, play_time: f32) -> Game {
+        let mut game = Game::new(GameMode::Classic);
+        game.score = score;
+        game.play_time = play_time;
+        game
+    }
+
+    #[test]
+    fn deaths_and_abandoned_runs_both_count_as_games() {
+        let mut stats = LifetimeStats::default();
+
+        let mut died = played(4, 20.0);
+        died.game_over = true;
+        stats.on_game_event(&died, &GameEvent::Died { cause: DeathCause::SelfCollision });
+        stats.record_abandoned(&played(2, 10.0));
+
+        assert_eq!(stats.games_played, 2);
+        assert_eq!(stats.total_score, 6);
+        assert_eq!(stats.total_play_time, 30.0);
+        assert_eq!(stats.self_collision_deaths, 1);
+        assert_eq!(stats.average_score(), 3.0);
+        assert_eq!(stats.recent_games.iter().map(|g| g.score).collect::<Vec<_>>(), vec![4, 2]);
+    }
+
+    #[test]
+    fn finished_and_unstarted_runs_are_not_counted_again() {
+        let mut stats = LifetimeStats::default();
+
+        let mut finished = played(5, 12.0);
+        finished.game_over = true;
+        stats.record_abandoned(&finished);
+        stats.record_abandoned(&played(0, 0.0));
+
+        assert_eq!(stats.games_played, 0);
+        assert_eq!(stats.average_score(), 0.0);
+    }
+
+    #[test]
+    fn recent_games_keep_only_the_latest() {
+        let mut stats = LifetimeStats::default();
+        for score in 0..RECENT_GAMES_LIMIT as u32 + 5 {
+            stats.record_abandoned(&played(score, 1.0));
+        }
+
+        assert_eq!(stats.recent_games.len(), RECENT_GAMES_LIMIT);
+        assert_eq!(stats.recent_games[0].score, 5);
+        assert_eq!(stats.games_played, RECENT_GAMES_LIMIT as u32 + 5);
+    }
+
+    #[test]
+    fn play_time_switches_to_hours_after_an_hour() {
+        assert_eq!(format_play_time(59.9), "0m 59s");
+        assert_eq!(format_play_time(3599.0), "59m 59s");
+        assert_eq!(format_play_time(3600.0 + 5.0 * 60.0), "1h 05m");
+    }
+}
@@ -0,0 +1,348 @@
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use eframe::egui;
+use serde::Deserialize;
+use crate::resources::UserProfile;
+
+const MANIFEST_FILE: &str = "theme.json";
+const RELOAD_POLL_INTERVAL: f64 = 0.5;
+
+/// Packs compiled into the binary so they are available on every platform.
+const BUNDLED_THEMES: [(&str, &str); 2] = [
+    ("midnight", include_str!("../assets/themes/midnight/theme.json")),
+    ("paper", include_str!("../assets/themes/paper/theme.json")),
+];
+
+#[derive(Deserialize, Clone)]
+pub struct ThemeManifest {
+    pub name: String,
+    #[serde(default)]
+    pub board: BoardTheme,
+    /// Sheet of four square tiles facing up: head, straight body, corner, tail.
+    #[serde(default)]
+    pub snake_sprite: Option<String>,
+    #[serde(default)]
+    pub apple_sprite: Option<String>,
+    #[serde(default)]
+    pub visuals: VisualsTheme,
+}
+
+#[derive(Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct BoardTheme {
+    /// Falls back to the colour picked on the profile screen.
+    pub background: Option<[u8; 3]>,
+    pub border: Option<[u8; 3]>,
+    pub grid: GridTheme,
+}
+
+#[derive(Deserialize, Clone, Copy, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum GridStyle {
+    #[default]
+    Lines,
+    Dots,
+    None,
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(default)]
+pub struct GridTheme {
+    pub style: GridStyle,
+    pub color: [u8; 3],
+    /// In physical pixels.
+    pub width: f32,
+}
+
+impl Default for GridTheme {
+    fn default() -> Self {
+        Self {
+            style: GridStyle::Lines,
+            color: [40, 40, 40],
+            width: 1.0,
+        }
+    }
+}
+
+#[derive(Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct VisualsTheme {
+    pub dark_mode: Option<bool>,
+    pub panel_fill: Option<[u8; 3]>,
+    pub window_fill: Option<[u8; 3]>,
+    pub widget_fill: Option<[u8; 3]>,
+    pub widget_hover_fill: Option<[u8; 3]>,
+    pub widget_active_fill: Option<[u8; 3]>,
+    pub text_color: Option<[u8; 3]>,
+    pub rounding: Option<f32>,
+}
+
+impl VisualsTheme {
+    /// Starts from `default` when the pack doesn't pick dark or light mode.
+    pub fn to_visuals(&self, default: &egui::Visuals) -> egui::Visuals {
+        let mut visuals = match self.dark_mode {
+            Some(true) => egui::Visuals::dark(),
+            Some(false) => egui::Visuals::light(),
+            None => default.clone(),
+        };
+
+        if let Some(fill) = self.panel_fill {
+            visuals.panel_fill = rgb(fill);
+        }
+        if let Some(fill) = self.window_fill {
+            visuals.window_fill = rgb(fill);
+            visuals.extreme_bg_color = rgb(fill);
+        }
+        if let Some(fill) = self.widget_fill {
+            visuals.widgets.inactive.bg_fill = rgb(fill);
+            visuals.widgets.inactive.weak_bg_fill = rgb(fill);
+        }
+        if let Some(fill) = self.widget_hover_fill {
+            visuals.widgets.hovered.bg_fill = rgb(fill);
+            visuals.widgets.hovered.weak_bg_fill = rgb(fill);
+        }
+        if let Some(fill) = self.widget_active_fill {
+            visuals.widgets.active.bg_fill = rgb(fill);
+            visuals.widgets.active.weak_bg_fill = rgb(fill);
+            visuals.selection.bg_fill = rgb(fill);
+        }
+        if let Some(color) = self.text_color {
+            visuals.override_text_color = Some(rgb(color));
+        }
+        if let Some(radius) = self.rounding {
+            let rounding = egui::Rounding::same(radius);
+            visuals.widgets.inactive.rounding = rounding;
+            visuals.widgets.hovered.rounding = rounding;
+            visuals.widgets.active.rounding = rounding;
+            visuals.window_rounding = rounding;
+        }
+
+        visuals
+    }
+}
+
+#[derive(Clone, PartialEq)]
+pub enum ThemeSource {
+    Bundled(&'static str),
+    /// Pack directory holding `theme.json` and its sprites.
+    Disk(PathBuf),
+}
+
+pub struct ThemeInfo {
+    pub id: String,
+    pub name: String,
+    pub source: ThemeSource,
+}
+
+pub struct Theme {
+    pub id: String,
+    pub manifest: ThemeManifest,
+    pub snake_sprite: Option<egui::TextureHandle>,
+    pub apple_sprite: Option<egui::TextureHandle>,
+}
+
+/// Board colours after the theme has been layered over the player's profile.
+pub struct BoardStyle {
+    pub background: egui::Color32,
+    pub border: egui::Color32,
+    pub grid_style: GridStyle,
+    pub grid_color: egui::Color32,
+    pub grid_width: f32,
+}
+
+pub fn board_style(theme: Option<&Theme>, profile: &UserProfile) -> BoardStyle {
+    let board = theme.map(|t| t.manifest.board.clone()).unwrap_or_default();
+
+    BoardStyle {
+        background: rgb(board.background.unwrap_or(profile.background_color)),
+        border: board.border.map(rgb).unwrap_or(egui::Color32::from_gray(180)),
+        grid_style: board.grid.style,
+        grid_color: rgb(board.grid.color),
+        grid_width: board.grid.width,
+    }
+}
+
+/// Lists the available packs, keeps the selected one loaded, and reloads it
+/// whenever one of its files changes on disk.
+pub struct ThemeManager {
+    themes_dir: Option<PathBuf>,
+    pub available: Vec<ThemeInfo>,
+    pub active: Option<Theme>,
+    pub error: Option<String>,
+    selected: Option<String>,
+    watched: Vec<(PathBuf, Option<SystemTime>)>,
+    last_poll: f64,
+    /// The app's look before any pack was applied, restored for "Default".
+    default_visuals: egui::Visuals,
+}
+
+impl ThemeManager {
+    pub fn new(ctx: &egui::Context, themes_dir: Option<PathBuf>) -> Self {
+        let mut manager = Self {
+            default_visuals: ctx.style().visuals.clone(),
+            themes_dir,
+            available: Vec::new(),
+            active: None,
+            error: None,
+            selected: None,
+            watched: Vec::new(),
+            last_poll: f64::NEG_INFINITY,
+        };
+        manager.rescan();
+        manager
+    }
+
+    pub fn themes_dir(&self) -> Option<&Path> {
+        self.themes_dir.as_deref()
+    }
+
+    /// Packs in the themes directory shadow bundled packs with the same id, so an
+    /// artist can copy a bundled pack out and iterate on it.
+    pub fn rescan(&mut self) {
+        let mut available: Vec<ThemeInfo> = BUNDLED_THEMES
+            .iter()
+            .filter_map(|&(id, json)| {
+                let manifest = serde_json::from_str::<ThemeManifest>(json).ok()?;
+                Some(ThemeInfo {
+                    id: id.to_string(),
+                    name: manifest.name,
+                    source: ThemeSource::Bundled(json),
+                })
+            })
+            .collect();
+
+        if let Some(entries) = self.themes_dir.as_ref().and_then(|dir| std::fs::read_dir(dir).ok()) {
+            for entry in entries.flatten() {
+                let dir = entry.path();
+                let Some(id) = dir.file_name().and_then(|n| n.to_str()).map(str::to_string) else {
+                    continue;
+                };
+                let Ok(json) = std::fs::read_to_string(dir.join(MANIFEST_FILE)) else {
+                    continue;
+                };
+                let name = serde_json::from_str::<ThemeManifest>(&json)
+                    .map(|m| m.name)
+                    .unwrap_or_else(|_| id.clone());
+
+                available.retain(|info| info.id != id);
+                available.push(ThemeInfo { id, name, source: ThemeSource::Disk(dir) });
+            }
+        }
+
+        available.sort_by(|a, b| a.name.cmp(&b.name));
+        self.available = available;
+    }
+
+    /// Call once per frame with the pack chosen in settings.
+    pub fn update(&mut self, ctx: &egui::Context, selected: Option<&str>, now: f64) {
+        let selection_changed = self.selected.as_deref() != selected;
+        let mut reload = selection_changed;
+
+        if now - self.last_poll >= RELOAD_POLL_INTERVAL {
+            self.last_poll = now;
+            reload |= self.files_changed();
+        }
+
+        if !reload {
+            return;
+        }
+
+        self.selected = selected.map(str::to_string);
+        if selection_changed {
+            self.rescan();
+        }
+
+        match selected {
+            None => {
+                self.active = None;
+                self.error = None;
+                self.watched.clear();
+                ctx.set_visuals(self.default_visuals.clone());
+            }
+            Some(id) => match self.load(ctx, id) {
+                Ok(theme) => {
+                    ctx.set_visuals(theme.manifest.visuals.to_visuals(&self.default_visuals));
+                    self.active = Some(theme);
+                    self.error = None;
+                }
+                // Keep showing the last good version so a half-saved file doesn't flash the UI.
+                Err(err) => self.error = Some(err),
+            },
+        }
+    }
+
+    fn files_changed(&mut self) -> bool {
+        let mut changed = false;
+        for (path, modified) in &mut self.watched {
+            let current = std::fs::metadata(&path).and_then(|m| m.modified()).ok();
+            if current != *modified {
+                *modified = current;
+                changed = true;
+            }
+        }
+        changed
+    }
+
+    fn load(&mut self, ctx: &egui::Context, id: &str) -> Result<Theme, String> {
+        let source = self
+            .available
+            .iter()
+            .find(|info| info.id == id)
+            .map(|info| info.source.clone())
+            .ok_or_else(|| format!("Theme \"{}\" was not found", id))?;
+
+        self.watched.clear();
+
+        let (json, dir) = match source {
+            ThemeSource::Bundled(json) => (json.to_string(), None),
+            ThemeSource::Disk(dir) => {
+                let manifest_path = dir.join(MANIFEST_FILE);
+                self.watch(&manifest_path);
+                let json = std::fs::read_to_string(&manifest_path)
+                    .map_err(|e| format!("Could not read {}: {}", manifest_path.display(), e))?;
+                (json, Some(dir))
+            }
+        };
+
+        let manifest: ThemeManifest = serde_json::from_str(&json)
+            .map_err(|e| format!("Invalid theme \"{}\": {}", id, e))?;
+
+        let mut load_sprite = |file: &Option<String>, name: &str| -> Result<Option<egui::TextureHandle>, String> {
+            let (Some(file), Some(dir)) = (file, dir.as_ref()) else {
+                return Ok(None);
+            };
+            let path = dir.join(file);
+            self.watch(&path);
+            load_png(ctx, &path, name).map(Some)
+        };
+
+        let snake_sprite = load_sprite(&manifest.snake_sprite, "theme_snake_sprite")?;
+        let apple_sprite = load_sprite(&manifest.apple_sprite, "theme_apple_sprite")?;
+
+        Ok(Theme {
+            id: id.to_string(),
+            manifest,
+            snake_sprite,
+            apple_sprite,
+        })
+    }
+
+    fn watch(&mut self, path: &Path) {
+        let modified = std::fs::metadata(path).and_then(|m| m.modified()).ok();
+        self.watched.push((path.to_path_buf(), modified));
+    }
+}
+
+fn load_png(ctx: &egui::Context, path: &Path, name: &str) -> Result<egui::TextureHandle, String> {
+    let bytes = std::fs::read(path).map_err(|e| format!("Could not read {}: {}", path.display(), e))?;
+    let image = image::load_from_memory_with_format(&bytes, image::ImageFormat::Png)
+        .map_err(|e| format!("Could not decode {}: {}", path.display(), e))?
+        .to_rgba8();
+    let size = [image.width() as usize, image.height() as usize];
+    let color_image = egui::ColorImage::from_rgba_unmultiplied(size, image.as_raw());
+    Ok(ctx.load_texture(name, color_image, egui::TextureOptions::NEAREST))
+}
+
+fn rgb(color: [u8; 3]) -> egui::Color32 {
+    egui::Color32::from_rgb(color[0], color[1], color[2])
+}
@@ -0,0 +1,18 @@
+use eframe::egui;
+use crate::resources::ConnectionStatus;
+
+/// A coloured dot with a short label describing the last leaderboard request.
+pub fn show(ui: &mut egui::Ui, status: ConnectionStatus) {
+    let color = match status {
+        ConnectionStatus::Unknown => egui::Color32::GRAY,
+        ConnectionStatus::Online => egui::Color32::from_rgb(0, 200, 90),
+        ConnectionStatus::ServerError => egui::Color32::from_rgb(255, 170, 0),
+        ConnectionStatus::Offline | ConnectionStatus::Misconfigured => egui::Color32::from_rgb(255, 90, 90),
+    };
+
+    ui.horizontal(|ui| {
+        let (rect, _) = ui.allocate_exact_size(egui::vec2(10.0, 10.0), egui::Sense::hover());
+        ui.painter().circle_filled(rect.center(), 4.0, color);
+        ui.label(egui::RichText::new(status.label()).size(12.0).color(egui::Color32::GRAY));
+    });
+}
@@ -1 +1,2 @@
+pub mod connection;
 pub mod keyboard;
\ No newline at end of file
@@ -0,0 +1,45 @@
+use eframe::egui;
+use crate::resources::GameState;
+
+/// Returns `true` once the player has confirmed they want to quit.
+pub fn show_quit_dialog(ctx: &egui::Context, state: &mut GameState) -> bool {
+    let mut quit = false;
+
+    egui::Area::new(egui::Id::new("quit_backdrop"))
+        .order(egui::Order::Middle)
+        .fixed_pos(ctx.screen_rect().min)
+        .show(ctx, |ui| {
+            let screen = ctx.screen_rect();
+            ui.painter().rect_filled(screen, 0.0, egui::Color32::from_black_alpha(170));
+            ui.allocate_rect(screen, egui::Sense::click());
+        });
+
+    egui::Window::new("Quit Snake?")
+        .order(egui::Order::Foreground)
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+        .show(ctx, |ui| {
+            ui.vertical_centered(|ui| {
+                ui.add_space(8.0);
+                ui.label(egui::RichText::new("Your progress will be saved.").size(14.0).color(egui::Color32::GRAY));
+                ui.add_space(16.0);
+
+                ui.horizontal(|ui| {
+                    if ui.add_sized([110.0, 40.0], egui::Button::new(
+                        egui::RichText::new("Cancel").size(16.0)
+                    )).clicked() {
+                        state.confirm_quit = false;
+                    }
+
+                    if ui.add_sized([110.0, 40.0], egui::Button::new(
+                        egui::RichText::new("Quit").size(16.0)
+                    )).clicked() {
+                        quit = true;
+                    }
+                });
+            });
+        });
+
+    quit
+}
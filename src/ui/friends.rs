@@ -0,0 +1,217 @@
+use eframe::egui;
+use crate::friends::{self, FriendList};
+use crate::resources::{GameState, QRCodeTextures, UserProfile};
+use crate::ui::share;
+#[cfg(target_os = "android")]
+use crate::ui::components;
+
+const TOP_SAFE_AREA: f32 = 24.0;
+const BOTTOM_SAFE_AREA: f32 = 24.0;
+
+pub enum FriendsChange {
+    List,
+    SyncEnabled,
+    SyncDisabled,
+}
+
+/// Text typed into the add-friend box and the outcome of the last attempt.
+#[derive(Clone, Default)]
+struct AddFriendState {
+    code: String,
+    message: Option<(String, bool)>,
+}
+
+fn add_friend_state_id() -> egui::Id {
+    egui::Id::new("add_friend_state")
+}
+
+#[cfg(target_os = "android")]
+pub fn show_friends_screen(
+    ui: &mut egui::Ui,
+    state: &mut GameState,
+    friend_list: &mut FriendList,
+    profile: &UserProfile,
+    qr_textures: &mut QRCodeTextures,
+    show_keyboard: &mut bool,
+) -> Option<FriendsChange> {
+    let mut change = None;
+    ui.add_space(TOP_SAFE_AREA);
+
+    egui::ScrollArea::vertical().show(ui, |ui| {
+        ui.vertical_centered(|ui| {
+            change = draw_friends_ui(ui, state, friend_list, profile, qr_textures, Some(show_keyboard));
+            ui.add_space(300.0);
+        });
+    });
+
+    let ctx = ui.ctx().clone();
+    let mut add_state: AddFriendState = ctx.data(|d| d.get_temp(add_friend_state_id()).unwrap_or_default());
+    if components::keyboard::show(&ctx, show_keyboard, &mut add_state.code) {
+        add_state.message = None;
+    }
+    ctx.data_mut(|d| d.insert_temp(add_friend_state_id(), add_state));
+
+    change
+}
+
+#[cfg(not(target_os = "android"))]
+pub fn show_friends_screen(
+    ui: &mut egui::Ui,
+    state: &mut GameState,
+    friend_list: &mut FriendList,
+    profile: &UserProfile,
+    qr_textures: &mut QRCodeTextures,
+) -> Option<FriendsChange> {
+    let mut change = None;
+    ui.add_space(TOP_SAFE_AREA);
+
+    egui::ScrollArea::vertical().show(ui, |ui| {
+        ui.vertical_centered(|ui| {
+            change = draw_friends_ui(ui, state, friend_list, profile, qr_textures, None);
+        });
+    });
+
+    change
+}
+
+fn draw_friends_ui(
+    ui: &mut egui::Ui,
+    state: &mut GameState,
+    friend_list: &mut FriendList,
+    profile: &UserProfile,
+    qr_textures: &mut QRCodeTextures,
+    mut mobile_keyboard_trigger: Option<&mut bool>,
+) -> Option<FriendsChange> {
+    let mut change = None;
+    let code = friends::friend_code(&profile.user_id);
+
+    if qr_textures.friend_qr.is_none() {
+        qr_textures.friend_qr = Some(share::qr_texture(ui.ctx(), &code, "friend_qr"));
+    }
+
+    ui.horizontal(|ui| {
+        if ui.add_sized([70.0, 35.0], egui::Button::new(egui::RichText::new("Back").size(12.5))).clicked() {
+            state.back();
+            if let Some(trigger) = mobile_keyboard_trigger.as_deref_mut() {
+                *trigger = false;
+            }
+        }
+    });
+
+    ui.add_space(20.0);
+    ui.heading(egui::RichText::new("Friends").size(28.0));
+    ui.add_space(25.0);
+
+    ui.group(|ui| {
+        ui.set_width(ui.available_width());
+        ui.vertical_centered(|ui| {
+            ui.label(egui::RichText::new("Your Friend Code").size(14.0).color(egui::Color32::GRAY));
+            ui.add_space(10.0);
+            if let Some(handle) = &qr_textures.friend_qr {
+                ui.image((handle.id(), egui::vec2(140.0, 140.0)));
+            }
+            ui.add_space(8.0);
+            ui.label(egui::RichText::new(&profile.user_id).size(16.0).monospace());
+            if ui.button("Copy code").clicked() {
+                ui.output_mut(|o| o.copied_text = code.clone());
+            }
+            ui.label(egui::RichText::new("Send this code to friends to paste under Add a Friend")
+                .size(12.0)
+                .color(egui::Color32::GRAY));
+        });
+    });
+
+    ui.add_space(15.0);
+
+    let mut add_state: AddFriendState = ui.data(|d| d.get_temp(add_friend_state_id()).unwrap_or_default());
+
+    ui.group(|ui| {
+        ui.set_width(ui.available_width());
+        ui.vertical(|ui| {
+            ui.label(egui::RichText::new("Add a Friend").size(14.0).color(egui::Color32::GRAY));
+            ui.add_space(5.0);
+
+            let mut submit = false;
+            ui.horizontal(|ui| {
+                let field_width = ui.available_width() - 70.0;
+                if let Some(trigger) = mobile_keyboard_trigger {
+                    let display = if add_state.code.is_empty() { "Tap to enter a code" } else { &add_state.code };
+                    if ui.add_sized([field_width, 35.0], egui::Button::new(egui::RichText::new(display).size(14.0))).clicked() {
+                        *trigger = !*trigger;
+                    }
+                } else {
+                    let response = ui.add_sized(
+                        [field_width, 35.0],
+                        egui::TextEdit::singleline(&mut add_state.code).hint_text("Friend code or user ID"),
+                    );
+                    if response.changed() {
+                        add_state.message = None;
+                    }
+                    submit |= response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                }
+
+                submit |= ui.add_sized([60.0, 35.0], egui::Button::new("Add")).clicked();
+            });
+
+            if submit && !add_state.code.trim().is_empty() {
+                add_state.message = Some(match friend_list.add(&add_state.code, &profile.user_id) {
+                    Ok(()) => {
+                        add_state.code.clear();
+                        change = Some(FriendsChange::List);
+                        ("Friend added".to_string(), true)
+                    }
+                    Err(err) => (err.to_string(), false),
+                });
+            }
+
+            if let Some((message, ok)) = &add_state.message {
+                let color = if *ok { egui::Color32::from_rgb(0, 255, 100) } else { egui::Color32::from_rgb(255, 100, 100) };
+                ui.label(egui::RichText::new(message).size(12.0).color(color));
+            }
+        });
+    });
+
+    ui.data_mut(|d| d.insert_temp(add_friend_state_id(), add_state));
+
+    ui.add_space(15.0);
+
+    ui.group(|ui| {
+        ui.set_width(ui.available_width());
+        ui.vertical(|ui| {
+            ui.label(egui::RichText::new("Your Friends").size(14.0).color(egui::Color32::GRAY));
+            ui.add_space(5.0);
+
+            if friend_list.friends.is_empty() {
+                ui.label(egui::RichText::new("No friends yet").size(14.0));
+            }
+
+            let mut removed = None;
+            for friend in &friend_list.friends {
+                ui.horizontal(|ui| {
+                    ui.label(egui::RichText::new(&friend.name).size(16.0));
+                    ui.label(egui::RichText::new(friends::short_id(&friend.user_id))
+                        .size(12.0)
+                        .color(egui::Color32::GRAY));
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui.button("Remove").clicked() {
+                            removed = Some(friend.user_id.clone());
+                        }
+                    });
+                });
+            }
+
+            if let Some(user_id) = removed {
+                friend_list.remove(&user_id);
+                change = Some(FriendsChange::List);
+            }
+
+            ui.add_space(8.0);
+            if ui.checkbox(&mut friend_list.sync, "Back up my friends list online").changed() {
+                change = Some(if friend_list.sync { FriendsChange::SyncEnabled } else { FriendsChange::SyncDisabled });
+            }
+        });
+    });
+
+    ui.add_space(BOTTOM_SAFE_AREA);
+    change
+}
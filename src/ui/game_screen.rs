@@ -1,34 +1,112 @@
 use eframe::egui;
 
-use crate::resources::{Game, GameState, Screen, UserProfile, Direction};
-use crate::constants::{GRID_SIZE, CELL_SIZE};
+use crate::effects::{Effects, ParticleColor};
+use crate::game_logic;
+use crate::palette;
+use crate::input::{relative, swipe};
+use crate::theme::{self, BoardStyle, GridStyle, Theme};
+use crate::ui::snake_skin::{draw_snake, draw_snake_sprites, SnakeLayout};
+use crate::resources::{Game, GameState, Screen, Settings, UserProfile, Direction};
+use crate::constants::{GRID_HEIGHT, GRID_WIDTH, MAX_CELL_SIZE, MIN_CELL_SIZE};
 
 const TOP_SAFE_AREA: f32 = 24.0;
 const BOTTOM_SAFE_AREA: f32 = 24.0;
+const PAUSE_ROW_HEIGHT: f32 = 45.0 + 20.0 + 30.0 + BOTTOM_SAFE_AREA;
+const TURN_ZONE_HEIGHT: f32 = 140.0;
+const DPAD_SIZE: f32 = 76.0 * 3.0 + 8.0 * 2.0;
+const LANDSCAPE_CONTROLS_WIDTH: f32 = DPAD_SIZE + 40.0 + 48.0;
+
+pub enum GameScreenAction {
+    /// The Back button, which the app handles the same way as the system back
+    /// gesture.
+    Back,
+    Restart,
+    QuitToMenu,
+}
 
 pub fn show_game_screen(
     ui: &mut egui::Ui,
     state: &mut GameState,
     game: &mut Game,
     profile: &UserProfile,
-) {
+    effects: &mut Effects,
+    settings: &Settings,
+    theme: Option<&Theme>,
+) -> Option<GameScreenAction> {
     let screen_width = ui.available_width();
     let screen_height = ui.available_height();
     let is_landscape = screen_width > screen_height;
 
-    if is_landscape {
-        show_game_screen_landscape(ui, state, game, profile);
+    let back = if is_landscape {
+        show_game_screen_landscape(ui, game, profile, effects, settings, theme)
     } else {
-        show_game_screen_portrait(ui, state, game, profile);
+        show_game_screen_portrait(ui, game, profile, effects, settings, theme)
+    };
+
+    if game.paused && !game.game_over {
+        if let Some(action) = show_pause_menu(ui.ctx(), state, game) {
+            return Some(action);
+        }
     }
+
+    back.then_some(GameScreenAction::Back)
+}
+
+fn show_pause_menu(ctx: &egui::Context, state: &mut GameState, game: &mut Game) -> Option<GameScreenAction> {
+    let mut action = None;
+    let screen = ctx.screen_rect();
+
+    // Swallows clicks so nothing behind the menu reacts while paused.
+    egui::Area::new(egui::Id::new("pause_backdrop"))
+        .order(egui::Order::Middle)
+        .fixed_pos(screen.min)
+        .show(ctx, |ui| {
+            ui.painter().rect_filled(screen, 0.0, egui::Color32::from_black_alpha(170));
+            ui.allocate_rect(screen, egui::Sense::click());
+        });
+
+    egui::Area::new(egui::Id::new("pause_menu"))
+        .order(egui::Order::Foreground)
+        .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+        .show(ctx, |ui| {
+            egui::Frame::popup(ui.style()).inner_margin(24.0).show(ui, |ui| {
+                ui.vertical_centered(|ui| {
+                    ui.heading(egui::RichText::new("Paused").size(28.0));
+                    ui.add_space(20.0);
+
+                    let button = |text: &str| egui::Button::new(egui::RichText::new(text).size(18.0));
+
+                    if ui.add_sized([200.0, 50.0], button("Resume")).clicked() {
+                        game_logic::resume(game);
+                    }
+                    ui.add_space(8.0);
+                    if ui.add_sized([200.0, 50.0], button("Restart")).clicked() {
+                        action = Some(GameScreenAction::Restart);
+                    }
+                    ui.add_space(8.0);
+                    if ui.add_sized([200.0, 50.0], button("Settings")).clicked() {
+                        state.navigate(Screen::Settings);
+                    }
+                    ui.add_space(8.0);
+                    if ui.add_sized([200.0, 50.0], button("Quit to Menu")).clicked() {
+                        action = Some(GameScreenAction::QuitToMenu);
+                    }
+                });
+            });
+        });
+
+    action
 }
 
 fn show_game_screen_portrait(
     ui: &mut egui::Ui,
-    state: &mut GameState,
     game: &mut Game,
     profile: &UserProfile,
-) {
+    effects: &mut Effects,
+    settings: &Settings,
+    theme: Option<&Theme>,
+) -> bool {
+    let mut back = false;
     ui.add_space(TOP_SAFE_AREA);
     
     ui.vertical_centered(|ui| {
@@ -36,12 +114,11 @@ fn show_game_screen_portrait(
             if ui.add_sized([70.0, 35.0], egui::Button::new(
                 egui::RichText::new("Back").size(12.5)
             )).clicked() {
-                state.current_screen = Screen::MainMenu;
-                *game = Game::default();
+                back = true;
             }
             
             ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                ui.label(egui::RichText::new(format!("Best: {}", profile.high_score))
+                ui.label(egui::RichText::new(format!("Best: {}", profile.best(game.mode)))
                     .size(16.0)
                     .color(egui::Color32::GRAY));
             });
@@ -55,7 +132,22 @@ fn show_game_screen_portrait(
         
         ui.add_space(15.0);
         
-        draw_game_canvas(ui, game, profile);
+        let mode = settings.controls.mode;
+        let show_dpad = mode.shows_dpad();
+        let mut reserved = PAUSE_ROW_HEIGHT;
+        if show_dpad {
+            reserved += DPAD_SIZE + 15.0;
+        } else if mode.uses_relative() {
+            reserved += TURN_ZONE_HEIGHT + 15.0;
+        }
+        let cell_size = fitted_cell_size(
+            ui.ctx(),
+            (GRID_WIDTH, GRID_HEIGHT),
+            ui.available_width() - 24.0,
+            ui.available_height() - reserved,
+        );
+        let canvas = draw_game_canvas(ui, game, profile, effects, theme, settings.display.high_contrast, cell_size);
+        handle_canvas_input(ui, &canvas, game, settings);
         
         if game.game_over {
             ui.add_space(30.0);
@@ -63,51 +155,73 @@ fn show_game_screen_portrait(
                 egui::Color32::from_rgb(255, 100, 100),
                 egui::RichText::new("Game Over").size(28.0)
             );
+            if game.resumed {
+                ui.label(egui::RichText::new("Resumed runs are not ranked")
+                    .size(12.0)
+                    .color(egui::Color32::GRAY));
+            } else if profile.new_best {
+                ui.label(egui::RichText::new(format!("New best for {}!", game.mode.label()))
+                    .size(16.0)
+                    .color(egui::Color32::from_rgb(255, 215, 0)));
+            }
             ui.add_space(20.0);
             if ui.add_sized([200.0, 55.0], egui::Button::new(
                 egui::RichText::new("Play Again").size(18.0)
             )).clicked() {
-                *game = Game::default();
+                *game = game.restarted();
+                effects.clear();
             }
         } else {
-            let dpad_total_height = 76.0 * 3.0 + 8.0 * 2.0 + 20.0 + 45.0 + BOTTOM_SAFE_AREA;
-            let remaining = ui.available_height() - dpad_total_height;
-            
-            if remaining > 0.0 {
-                ui.add_space(remaining * 0.7);
+            if show_dpad {
+                let dpad_total_height = DPAD_SIZE + 20.0 + 45.0 + BOTTOM_SAFE_AREA;
+                let remaining = ui.available_height() - dpad_total_height;
+                
+                if remaining > 0.0 {
+                    ui.add_space(remaining * 0.7);
+                } else {
+                    ui.add_space(20.0);
+                }
+                
+                ui.horizontal(|ui| {
+                    let available_width = ui.available_width();
+                    let padding = (available_width - DPAD_SIZE) / 2.0;
+                    
+                    ui.add_space(padding.max(0.0));
+                    
+                    draw_dpad_controls(ui, game);
+                });
+                
+                ui.add_space(15.0);
+            } else if mode.uses_relative() {
+                ui.add_space(20.0);
+                draw_turn_zones(ui, game, TURN_ZONE_HEIGHT);
+                ui.add_space(15.0);
             } else {
                 ui.add_space(20.0);
             }
             
-            ui.horizontal(|ui| {
-                let dpad_width = 76.0 * 3.0 + 8.0 * 2.0;
-                let available_width = ui.available_width();
-                let padding = (available_width - dpad_width) / 2.0;
-                
-                ui.add_space(padding.max(0.0));
-                
-                draw_dpad_controls(ui, game);
-            });
-            
-            ui.add_space(15.0);
-            
             if ui.add_sized([120.0, 45.0], egui::Button::new(
-                egui::RichText::new(if game.paused { "Resume" } else { "Pause" }).size(16.0)
+                egui::RichText::new("Pause").size(16.0)
             )).clicked() {
-                game.paused = !game.paused;
+                game_logic::pause(game);
             }
             
             ui.add_space(BOTTOM_SAFE_AREA);
         }
     });
+
+    back
 }
 
 fn show_game_screen_landscape(
     ui: &mut egui::Ui,
-    state: &mut GameState,
     game: &mut Game,
     profile: &UserProfile,
-) {
+    effects: &mut Effects,
+    settings: &Settings,
+    theme: Option<&Theme>,
+) -> bool {
+    let mut back = false;
     ui.add_space(TOP_SAFE_AREA);
     
     ui.horizontal(|ui| {
@@ -118,17 +232,23 @@ fn show_game_screen_landscape(
                 if ui.add_sized([100.0, 50.0], egui::Button::new(
                     egui::RichText::new("← Back").size(18.0)
                 )).clicked() {
-                    state.current_screen = Screen::MainMenu;
-                    *game = Game::default();
+                    back = true;
                 }
             });
             
             ui.add_space(8.0);
             ui.heading(egui::RichText::new(format!("Score: {}", game.score)).size(24.0));
-            ui.label(egui::RichText::new(format!("Best: {}", profile.high_score)).size(14.0));
+            ui.label(egui::RichText::new(format!("Best: {}", profile.best(game.mode))).size(14.0));
             ui.add_space(15.0);
             
-            draw_game_canvas(ui, game, profile);
+            let cell_size = fitted_cell_size(
+                ui.ctx(),
+                (GRID_WIDTH, GRID_HEIGHT),
+                ui.available_width() - LANDSCAPE_CONTROLS_WIDTH,
+                ui.available_height() - BOTTOM_SAFE_AREA,
+            );
+            let canvas = draw_game_canvas(ui, game, profile, effects, theme, settings.display.high_contrast, cell_size);
+            handle_canvas_input(ui, &canvas, game, settings);
         });
         
         ui.add_space(40.0);
@@ -141,100 +261,264 @@ fn show_game_screen_landscape(
                     egui::Color32::from_rgb(255, 100, 100),
                     egui::RichText::new("Game Over").size(24.0)
                 );
+                if game.resumed {
+                    ui.label(egui::RichText::new("Resumed runs are not ranked")
+                        .size(12.0)
+                        .color(egui::Color32::GRAY));
+                } else if profile.new_best {
+                    ui.label(egui::RichText::new(format!("New best for {}!", game.mode.label()))
+                        .size(14.0)
+                        .color(egui::Color32::from_rgb(255, 215, 0)));
+                }
                 ui.add_space(20.0);
                 if ui.add_sized([160.0, 50.0], egui::Button::new(
                     egui::RichText::new("Play Again").size(16.0)
                 )).clicked() {
-                    *game = Game::default();
+                    *game = game.restarted();
+                    effects.clear();
                 }
             } else {
-                draw_dpad_controls(ui, game);
-                
-                ui.add_space(25.0);
+                if settings.controls.mode.shows_dpad() {
+                    draw_dpad_controls(ui, game);
+                    
+                    ui.add_space(25.0);
+                } else if settings.controls.mode.uses_relative() {
+                    draw_turn_zones(ui, game, DPAD_SIZE);
+                    
+                    ui.add_space(25.0);
+                }
                 
                 if ui.add_sized([120.0, 45.0], egui::Button::new(
-                    egui::RichText::new(if game.paused { "Resume" } else { "Pause" }).size(16.0)
+                    egui::RichText::new("Pause").size(16.0)
                 )).clicked() {
-                    game.paused = !game.paused;
+                    game_logic::pause(game);
                 }
             }
         });
         
         ui.add_space(24.0);
     });
+
+    back
+}
+
+/// Largest cell size that fits the board into the given space, snapped to whole
+/// physical pixels so every cell edge lands on a pixel boundary.
+fn fitted_cell_size(ctx: &egui::Context, (columns, rows): (i32, i32), width: f32, height: f32) -> f32 {
+    let fit = (width / columns as f32).min(height / rows as f32);
+    let pixels_per_point = ctx.pixels_per_point();
+    let snapped = (fit * pixels_per_point).floor() / pixels_per_point;
+    snapped.clamp(MIN_CELL_SIZE, MAX_CELL_SIZE)
+}
+
+fn handle_canvas_input(ui: &egui::Ui, canvas: &egui::Response, game: &mut Game, settings: &Settings) {
+    let mode = settings.controls.mode;
+
+    if mode.uses_swipe() {
+        if let Some(direction) = swipe::detect(ui.ctx(), canvas, &settings.controls.swipe) {
+            game_logic::queue_direction(game, direction);
+        }
+    }
+
+    if mode.uses_relative() {
+        if let Some(turn) = relative::tap_turn(ui.ctx(), canvas.rect) {
+            game_logic::queue_turn(game, turn);
+        }
+    }
 }
 
-fn draw_game_canvas(ui: &mut egui::Ui, game: &Game, profile: &UserProfile) {
-    let canvas_size = GRID_SIZE as f32 * CELL_SIZE;
+fn draw_turn_zones(ui: &mut egui::Ui, game: &mut Game, height: f32) {
+    let width = ui.available_width().min(360.0);
+    let (rect, _) = ui.allocate_exact_size(egui::vec2(width, height), egui::Sense::click());
+    let painter = ui.painter_at(rect);
+    let arrow_color = egui::Color32::from_gray(190);
+
+    let gap = 8.0;
+    let half = (rect.width() - gap) / 2.0;
+    let left = egui::Rect::from_min_size(rect.min, egui::vec2(half, rect.height()));
+    let right = egui::Rect::from_min_size(egui::pos2(left.right() + gap, rect.top()), egui::vec2(half, rect.height()));
+
+    for zone in [left, right] {
+        painter.rect_filled(zone, 8.0, egui::Color32::from_gray(40));
+    }
+    draw_arrow_left(&painter, egui::Rect::from_center_size(left.center(), egui::vec2(76.0, 76.0)), arrow_color);
+    draw_arrow_right(&painter, egui::Rect::from_center_size(right.center(), egui::vec2(76.0, 76.0)), arrow_color);
+
+    if let Some(turn) = relative::tap_turn(ui.ctx(), rect) {
+        game_logic::queue_turn(game, turn);
+    }
+}
+
+fn draw_game_canvas(
+    ui: &mut egui::Ui,
+    game: &Game,
+    profile: &UserProfile,
+    effects: &Effects,
+    theme: Option<&Theme>,
+    high_contrast: bool,
+    cell_size: f32,
+) -> egui::Response {
+    let style = theme::board_style(theme, profile);
+    let outline = {
+        let bg = style.background;
+        let [r, g, b] = palette::outline_for([bg.r(), bg.g(), bg.b()]);
+        egui::Color32::from_rgb(r, g, b)
+    };
+    let (columns, rows) = (GRID_WIDTH, GRID_HEIGHT);
     let (response, painter) = ui.allocate_painter(
-        egui::vec2(canvas_size, canvas_size),
-        egui::Sense::hover(),
+        egui::vec2(columns as f32 * cell_size, rows as f32 * cell_size),
+        egui::Sense::drag(),
     );
-    let rect = response.rect;
+    let board = egui::Rect::from_min_size(painter.round_pos_to_pixels(response.rect.min), response.rect.size());
 
-    painter.rect_stroke(
-        rect.expand(1.0),
-        0.0,
-        egui::Stroke::new(2.0, egui::Color32::from_gray(180)),
-    );
+    let border = if high_contrast { egui::Stroke::new(3.0, outline) } else { egui::Stroke::new(2.0, style.border) };
+    painter.rect_stroke(board.expand(1.0), 0.0, border);
 
-    painter.rect_filled(
-        rect.shrink(1.0), 
-        0.0, 
-        egui::Color32::from_rgb(
-            profile.background_color[0],
-            profile.background_color[1],
-            profile.background_color[2],
-        ),
+    painter.rect_filled(board.shrink(1.0), 0.0, style.background);
+
+    let rect = egui::Rect::from_min_size(
+        painter.round_pos_to_pixels(board.min + effects.shake_offset()),
+        board.size(),
     );
 
-    for i in 0..GRID_SIZE {
-        for j in 0..GRID_SIZE {
-            let x = rect.min.x + i as f32 * CELL_SIZE;
-            let y = rect.min.y + j as f32 * CELL_SIZE;
-            painter.rect_stroke(
-                egui::Rect::from_min_size(
-                    egui::pos2(x, y),
-                    egui::vec2(CELL_SIZE, CELL_SIZE),
-                ),
-                0.0,
-                egui::Stroke::new(0.3, egui::Color32::from_gray(40)),
-            );
+    draw_grid(ui.ctx(), &painter, rect, &style, (columns, rows), cell_size);
+
+    if !effects.hides_snake() {
+        let layout = SnakeLayout { snake: &game.snake, heading: game.direction };
+        match theme.and_then(|t| t.snake_sprite.as_ref()) {
+            Some(sprites) => draw_snake_sprites(&painter, rect.min, cell_size, &layout, sprites),
+            None => draw_snake(&painter, rect.min, cell_size, &layout, profile.skin, profile.snake_color),
+        }
+
+        if high_contrast {
+            for (i, &(x, y)) in game.snake.iter().enumerate() {
+                let cell = egui::Rect::from_min_size(
+                    rect.min + egui::vec2(x as f32, y as f32) * cell_size,
+                    egui::vec2(cell_size, cell_size),
+                );
+                let width = if i == 0 { 3.0 } else { 1.5 };
+                painter.rect_stroke(cell.shrink(cell_size * 0.05), cell_size * 0.2, egui::Stroke::new(width, outline));
+            }
         }
     }
 
-    for (i, &(x, y)) in game.snake.iter().enumerate() {
-        let px = rect.min.x + x as f32 * CELL_SIZE;
-        let py = rect.min.y + y as f32 * CELL_SIZE;
-        let mut color = profile.snake_color;
-        if i == 0 {
-            color = [
-                color[0].saturating_add(55),
-                color[1].saturating_add(55),
-                color[2].saturating_add(55),
+    let fx = rect.min.x + game.food.0 as f32 * cell_size;
+    let fy = rect.min.y + game.food.1 as f32 * cell_size;
+    if let Some(sprite) = theme.and_then(|t| t.apple_sprite.as_ref()) {
+        painter.image(
+            sprite.id(),
+            egui::Rect::from_min_size(egui::pos2(fx, fy), egui::vec2(cell_size, cell_size)),
+            egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(1.0, 1.0)),
+            egui::Color32::WHITE,
+        );
+    } else {
+        let apple_center = egui::pos2(fx + cell_size / 2.0, fy + cell_size / 2.0);
+        let apple_color = egui::Color32::from_rgb(
+            profile.apple_color[0],
+            profile.apple_color[1],
+            profile.apple_color[2],
+        );
+
+        if high_contrast {
+            // A diamond stays recognisable even when the apple and snake colours are confused.
+            let r = cell_size * 0.45;
+            let points = vec![
+                apple_center + egui::vec2(0.0, -r),
+                apple_center + egui::vec2(r, 0.0),
+                apple_center + egui::vec2(0.0, r),
+                apple_center + egui::vec2(-r, 0.0),
             ];
+            painter.add(egui::Shape::convex_polygon(points, apple_color, egui::Stroke::new(2.0, outline)));
+        } else {
+            painter.circle_filled(apple_center, cell_size / 2.8, apple_color);
+        }
+    }
+
+    draw_effects(&painter, rect, effects, profile, cell_size);
+
+    if game.resume_countdown > 0.0 {
+        painter.text(
+            board.center(),
+            egui::Align2::CENTER_CENTER,
+            format!("{}", game.resume_countdown.ceil() as u32),
+            egui::FontId::proportional(cell_size * 5.0),
+            egui::Color32::WHITE,
+        );
+    }
+
+    response
+}
+
+fn draw_grid(
+    ctx: &egui::Context,
+    painter: &egui::Painter,
+    rect: egui::Rect,
+    style: &BoardStyle,
+    (columns, rows): (i32, i32),
+    cell_size: f32,
+) {
+    // Widths are in physical pixels and positions snap to pixel centres so the grid never blurs.
+    let width = style.grid_width / ctx.pixels_per_point();
+
+    match style.grid_style {
+        GridStyle::Lines => {
+            let stroke = egui::Stroke::new(width, style.grid_color);
+            for i in 1..columns {
+                let x = painter.round_to_pixel_center(rect.min.x + i as f32 * cell_size);
+                painter.vline(x, rect.y_range(), stroke);
+            }
+            for j in 1..rows {
+                let y = painter.round_to_pixel_center(rect.min.y + j as f32 * cell_size);
+                painter.hline(rect.x_range(), y, stroke);
+            }
         }
+        GridStyle::Dots => {
+            for i in 1..columns {
+                for j in 1..rows {
+                    let pos = rect.min + egui::vec2(i as f32, j as f32) * cell_size;
+                    painter.circle_filled(painter.round_pos_to_pixels(pos), width, style.grid_color);
+                }
+            }
+        }
+        GridStyle::None => {}
+    }
+}
+
+fn draw_effects(
+    painter: &egui::Painter,
+    rect: egui::Rect,
+    effects: &Effects,
+    profile: &UserProfile,
+    cell_size: f32,
+) {
+    let resolve = |color: ParticleColor| -> egui::Color32 {
+        let rgb = match color {
+            ParticleColor::Food => profile.apple_color,
+            ParticleColor::Snake => profile.snake_color,
+        };
+        egui::Color32::from_rgb(rgb[0], rgb[1], rgb[2])
+    };
+
+    for particle in &effects.particles {
+        let pos = rect.min + particle.pos * cell_size;
+        let size = particle.size * cell_size;
         painter.rect_filled(
-            egui::Rect::from_min_size(
-                egui::pos2(px + 1.5, py + 1.5),
-                egui::vec2(CELL_SIZE - 3.0, CELL_SIZE - 3.0),
-            ),
-            3.0,
-            egui::Color32::from_rgb(color[0], color[1], color[2]),
+            egui::Rect::from_center_size(pos, egui::vec2(size, size)),
+            size * 0.25,
+            resolve(particle.color).gamma_multiply(particle.alpha()),
         );
     }
 
-    let fx = rect.min.x + game.food.0 as f32 * CELL_SIZE;
-    let fy = rect.min.y + game.food.1 as f32 * CELL_SIZE;
-    painter.circle_filled(
-        egui::pos2(fx + CELL_SIZE / 2.0, fy + CELL_SIZE / 2.0),
-        CELL_SIZE / 2.8,
-        egui::Color32::from_rgb(
-            profile.apple_color[0],
-            profile.apple_color[1],
-            profile.apple_color[2],
-        ),
-    );
+    for popup in &effects.popups {
+        let pos = rect.min + (popup.origin + popup.offset()) * cell_size;
+        painter.text(
+            pos,
+            egui::Align2::CENTER_BOTTOM,
+            &popup.text,
+            egui::FontId::proportional((cell_size * 0.9).max(12.0)),
+            resolve(popup.color).gamma_multiply(popup.alpha()),
+        );
+    }
 }
 
 fn draw_dpad_controls(ui: &mut egui::Ui, game: &mut Game) {
@@ -248,24 +532,24 @@ fn draw_dpad_controls(ui: &mut egui::Ui, game: &mut Game) {
             ui.label("");
 
             let up_btn = ui.add_sized(button_size, egui::Button::new(""));
-            if up_btn.clicked() && game.direction != Direction::Down {
-                game.direction = Direction::Up;
+            if up_btn.clicked() {
+                game_logic::queue_direction(game, Direction::Up);
             }
             draw_arrow_up(&ui.painter_at(up_btn.rect), up_btn.rect, arrow_color);
             
             ui.end_row();
 
             let left_btn = ui.add_sized(button_size, egui::Button::new(""));
-            if left_btn.clicked() && game.direction != Direction::Right {
-                game.direction = Direction::Left;
+            if left_btn.clicked() {
+                game_logic::queue_direction(game, Direction::Left);
             }
             draw_arrow_left(&ui.painter_at(left_btn.rect), left_btn.rect, arrow_color);
 
             ui.label("");
 
             let right_btn = ui.add_sized(button_size, egui::Button::new(""));
-            if right_btn.clicked() && game.direction != Direction::Left {
-                game.direction = Direction::Right;
+            if right_btn.clicked() {
+                game_logic::queue_direction(game, Direction::Right);
             }
             draw_arrow_right(&ui.painter_at(right_btn.rect), right_btn.rect, arrow_color);
 
@@ -274,8 +558,8 @@ fn draw_dpad_controls(ui: &mut egui::Ui, game: &mut Game) {
             ui.label("");
 
             let down_btn = ui.add_sized(button_size, egui::Button::new(""));
-            if down_btn.clicked() && game.direction != Direction::Up {
-                game.direction = Direction::Down;
+            if down_btn.clicked() {
+                game_logic::queue_direction(game, Direction::Down);
             }
             draw_arrow_down(&ui.painter_at(down_btn.rect), down_btn.rect, arrow_color);
 
@@ -1,11 +1,42 @@
 use eframe::egui;
-use crate::resources::{GameState, Screen, Leaderboard};
+use crate::period::{self, LeaderboardPeriod};
+use crate::resources::{ConnectionStatus, GameState, Screen, Leaderboard, LeaderboardEntry};
+use crate::ui::{components, snake_skin};
 
 const TOP_SAFE_AREA: f32 = 24.0;
 const BOTTOM_SAFE_AREA: f32 = 24.0;
+const ROW_HEIGHT: f32 = 40.0;
+const PINNED_HEADER_HEIGHT: f32 = 36.0;
 
-pub fn show_leaderboard_screen(ui: &mut egui::Ui, state: &mut GameState, leaderboard: &Leaderboard) -> bool {
+pub enum LeaderboardAction {
+    Refresh,
+    LoadMore,
+    Select {
+        period: LeaderboardPeriod,
+        friends_only: bool,
+    },
+}
+
+pub fn show_leaderboard_screen(
+    ui: &mut egui::Ui,
+    state: &mut GameState,
+    leaderboard: &Leaderboard,
+    player_id: &str,
+    has_friends: bool,
+    connection: ConnectionStatus,
+    pending_uploads: usize,
+) -> Option<LeaderboardAction> {
     let mut request_refresh = false;
+    let mut request_more = false;
+    let mut selected = None;
+    let now = period::now();
+    let period = leaderboard.board.period;
+    let friends_only = leaderboard.friends_only;
+
+    // The day or week ended while the screen was open.
+    if period.bucket(now) != leaderboard.board.bucket && !leaderboard.loading && leaderboard.error.is_none() {
+        request_refresh = true;
+    }
 
     ui.add_space(TOP_SAFE_AREA);
     
@@ -14,7 +45,7 @@ pub fn show_leaderboard_screen(ui: &mut egui::Ui, state: &mut GameState, leaderb
             if ui.add_sized([70.0, 35.0], egui::Button::new(
                 egui::RichText::new("Back").size(12.5)
             )).clicked() {
-                state.current_screen = Screen::MainMenu;
+                state.back();
             }
 
             ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
@@ -23,16 +54,107 @@ pub fn show_leaderboard_screen(ui: &mut egui::Ui, state: &mut GameState, leaderb
                 )).clicked() {
                     request_refresh = true;
                 }
+
+                if ui.add_sized([80.0, 35.0], egui::Button::new(
+                    egui::RichText::new("Profile").size(12.5)
+                )).clicked() {
+                    state.navigate(Screen::Profile);
+                }
+
+                if ui.add_sized([80.0, 35.0], egui::Button::new(
+                    egui::RichText::new("Friends").size(12.5)
+                )).clicked() {
+                    state.navigate(Screen::Friends);
+                }
             });
         });
         
         ui.add_space(20.0);
         
         ui.heading(egui::RichText::new("Leaderboard").size(28.0));
+        components::connection::show(ui, connection);
+        if pending_uploads > 0 {
+            let text = if pending_uploads == 1 {
+                "1 update waiting to upload".to_string()
+            } else {
+                format!("{} updates waiting to upload", pending_uploads)
+            };
+            ui.label(egui::RichText::new(text).size(12.0).color(egui::Color32::from_rgb(255, 200, 80)));
+        }
+        if let Some(standing) = &leaderboard.standing {
+            ui.label(egui::RichText::new(format!("Your rank: {}", rank_label(standing.rank, standing.capped))).size(14.0).color(egui::Color32::GRAY));
+        }
         
-        ui.add_space(30.0);
+        ui.add_space(16.0);
+
+        ui.horizontal(|ui| {
+            for option in LeaderboardPeriod::ALL {
+                if ui.selectable_label(option == period, egui::RichText::new(option.label()).size(14.0)).clicked()
+                    && option != period
+                {
+                    selected = Some((option, friends_only));
+                }
+            }
+        });
+
+        ui.horizontal(|ui| {
+            for (label, option) in [("Everyone", false), ("Friends", true)] {
+                if ui.selectable_label(option == friends_only, egui::RichText::new(label).size(14.0)).clicked()
+                    && option != friends_only
+                {
+                    selected = Some((period, option));
+                }
+            }
+        });
+
+        if let Some(reset) = period.next_reset(now) {
+            ui.label(egui::RichText::new(format!("Resets in {}", period::format_countdown(reset.saturating_sub(now))))
+                .size(12.0)
+                .color(egui::Color32::GRAY));
+            ui.ctx().request_repaint_after(std::time::Duration::from_secs(1));
+        }
+
+        if !leaderboard.previous_winners.is_empty() {
+            ui.add_space(8.0);
+            let winners = leaderboard
+                .previous_winners
+                .iter()
+                .enumerate()
+                .map(|(i, entry)| format!("#{} {} ({})", i + 1, entry.username, entry.score))
+                .collect::<Vec<_>>()
+                .join("   ");
+            ui.label(egui::RichText::new(format!("{} winners", period.previous_label())).size(12.0).strong());
+            ui.label(egui::RichText::new(winners).size(12.0).color(egui::Color32::from_rgb(255, 215, 0)));
+        }
+
+        ui.add_space(20.0);
+
+        if let Some(error) = &leaderboard.error {
+            let heading = if leaderboard.entries.is_empty() {
+                "Couldn't load the leaderboard"
+            } else {
+                "Showing the last scores we loaded"
+            };
+            ui.label(egui::RichText::new(heading).size(16.0).color(egui::Color32::from_rgb(255, 100, 100)));
+            ui.label(egui::RichText::new(error.to_string()).size(12.0).color(egui::Color32::GRAY));
+            ui.add_space(8.0);
+            if ui.add_enabled(!leaderboard.loading, egui::Button::new("Retry")).clicked() {
+                request_refresh = true;
+            }
+            ui.add_space(20.0);
+        }
         
-        if leaderboard.entries.is_empty() {
+        if friends_only && !has_friends {
+            ui.label(egui::RichText::new("No friends yet").size(18.0).color(egui::Color32::GRAY));
+            ui.add_space(15.0);
+            ui.label(egui::RichText::new("Add friends to compare scores").size(16.0));
+            ui.add_space(10.0);
+            if ui.button("Add Friends").clicked() {
+                state.navigate(Screen::Friends);
+            }
+        } else if leaderboard.entries.is_empty() && leaderboard.loading {
+            ui.spinner();
+        } else if leaderboard.entries.is_empty() && leaderboard.error.is_none() {
             ui.label(egui::RichText::new("No scores yet").size(18.0).color(egui::Color32::GRAY));
             ui.add_space(15.0);
             ui.label(egui::RichText::new("Play to set your first record!").size(16.0));
@@ -40,39 +162,104 @@ pub fn show_leaderboard_screen(ui: &mut egui::Ui, state: &mut GameState, leaderb
             if ui.button("Force Refresh").clicked() {
                 request_refresh = true;
             }
-        } else {
-            egui::ScrollArea::vertical().show(ui, |ui| {
-                for (i, entry) in leaderboard.entries.iter().take(10).enumerate() {
-                    ui.horizontal(|ui| {
-                        let rank_color = match i {
-                            0 => egui::Color32::from_rgb(255, 215, 0),
-                            1 => egui::Color32::from_rgb(192, 192, 192),
-                            2 => egui::Color32::from_rgb(205, 127, 50),
-                            _ => egui::Color32::GRAY,
-                        };
-
-                        ui.label(egui::RichText::new(format!("#{}", i + 1))
-                            .size(18.0)
-                            .color(rank_color)
-                            .strong());
-                        
-                        ui.add_space(10.0);
-                        
-                        ui.label(egui::RichText::new(&entry.username).size(16.0));
-                        
-                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                            ui.label(egui::RichText::new(format!("{}", entry.score))
-                                .size(18.0)
-                                .color(egui::Color32::from_rgb(0, 255, 100)));
-                        });
-                    });
-                    ui.add_space(12.0);
-                }
+        } else if !leaderboard.entries.is_empty() {
+            // Pin the player's neighbourhood below the list until their own row has
+            // been scrolled into the loaded pages.
+            let pinned = leaderboard
+                .standing
+                .as_ref()
+                .filter(|standing| standing.rank > leaderboard.entries.len());
+            let pinned_height = pinned.map_or(0.0, |standing| {
+                PINNED_HEADER_HEIGHT + standing.entries.len() as f32 * ROW_HEIGHT
             });
+
+            egui::ScrollArea::vertical()
+                .max_height((ui.available_height() - pinned_height - BOTTOM_SAFE_AREA).max(ROW_HEIGHT))
+                .show(ui, |ui| {
+                    for (i, entry) in leaderboard.entries.iter().enumerate() {
+                        draw_row(ui, i + 1, false, entry, entry.user_id == player_id);
+                    }
+
+                    if leaderboard.next_page.is_some() {
+                        let spinner = ui.spinner();
+                        let can_load = !leaderboard.loading && !leaderboard.loading_more && leaderboard.error.is_none();
+                        if can_load && ui.is_rect_visible(spinner.rect) {
+                            request_more = true;
+                        }
+                    }
+                });
+
+            if let Some(standing) = pinned {
+                ui.separator();
+                ui.label(egui::RichText::new("Your position").size(14.0).color(egui::Color32::GRAY));
+                for (i, entry) in standing.entries.iter().enumerate() {
+                    draw_row(ui, standing.first_rank + i, standing.capped, entry, entry.user_id == player_id);
+                }
+            }
         }
         
         ui.add_space(BOTTOM_SAFE_AREA);
     });
 
-    request_refresh
-}
\ No newline at end of file
+    if let Some((period, friends_only)) = selected {
+        Some(LeaderboardAction::Select { period, friends_only })
+    } else if request_refresh {
+        Some(LeaderboardAction::Refresh)
+    } else if request_more {
+        Some(LeaderboardAction::LoadMore)
+    } else {
+        None
+    }
+}
+
+/// `#12`, or `#1000+` when the rank is only known to be at least that.
+fn rank_label(rank: usize, at_least: bool) -> String {
+    if at_least {
+        format!("#{}+", rank)
+    } else {
+        format!("#{}", rank)
+    }
+}
+
+fn draw_row(ui: &mut egui::Ui, rank: usize, at_least: bool, entry: &LeaderboardEntry, is_player: bool) {
+    let fill = if is_player {
+        egui::Color32::from_rgba_unmultiplied(0, 255, 100, 30)
+    } else {
+        egui::Color32::TRANSPARENT
+    };
+
+    egui::Frame::none()
+        .fill(fill)
+        .rounding(6.0)
+        .inner_margin(egui::Margin::symmetric(8.0, 4.0))
+        .show(ui, |ui| {
+            ui.set_min_height(ROW_HEIGHT - 8.0);
+            ui.horizontal(|ui| {
+                let rank_color = match rank {
+                    1 => egui::Color32::from_rgb(255, 215, 0),
+                    2 => egui::Color32::from_rgb(192, 192, 192),
+                    3 => egui::Color32::from_rgb(205, 127, 50),
+                    _ => egui::Color32::GRAY,
+                };
+
+                ui.label(egui::RichText::new(rank_label(rank, at_least))
+                    .size(18.0)
+                    .color(rank_color)
+                    .strong());
+
+                ui.add_space(10.0);
+
+                snake_skin::draw_preview(ui, entry.skin, entry.snake_color, 10.0);
+                ui.add_space(6.0);
+
+                let name = egui::RichText::new(&entry.username).size(16.0);
+                ui.label(if is_player { name.strong() } else { name });
+
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    ui.label(egui::RichText::new(format!("{}", entry.score))
+                        .size(18.0)
+                        .color(egui::Color32::from_rgb(0, 255, 100)));
+                });
+            });
+        });
+}
@@ -1,9 +1,22 @@
 use eframe::egui;
-use crate::resources::{GameState, Screen, UserProfile};
+use crate::resources::{ConnectionStatus, GameState, Screen};
+use crate::ui::components;
+
+pub fn show_main_menu(
+    ui: &mut egui::Ui,
+    state: &mut GameState,
+    connection: ConnectionStatus,
+    has_suspended_game: bool,
+) -> bool {
+    let mut continue_game = false;
+
+    ui.with_layout(egui::Layout::right_to_left(egui::Align::Min), |ui| {
+        ui.add_space(12.0);
+        components::connection::show(ui, connection);
+    });
 
-pub fn show_main_menu(ui: &mut egui::Ui, state: &mut GameState, _profile: &UserProfile) {
     ui.vertical_centered(|ui| {
-        ui.add_space(60.0);
+        ui.add_space(40.0);
         
         ui.heading(egui::RichText::new("SNAKE").size(48.0).color(egui::Color32::from_rgb(0, 255, 100)));
         ui.label(egui::RichText::new("Classic Reimagined").color(egui::Color32::GRAY));
@@ -12,32 +25,48 @@ pub fn show_main_menu(ui: &mut egui::Ui, state: &mut GameState, _profile: &UserP
         
         let button_size = egui::vec2(240.0, 55.0);
         
+        if has_suspended_game {
+            if ui.add_sized(button_size, egui::Button::new("Continue")).clicked() {
+                continue_game = true;
+            }
+            
+            ui.add_space(15.0);
+        }
+        
         if ui.add_sized(button_size, egui::Button::new("Play")).clicked() {
-            state.current_screen = Screen::Playing;
+            state.navigate(Screen::Playing);
         }
         
         ui.add_space(15.0);
         
         if ui.add_sized(button_size, egui::Button::new("Settings")).clicked() {
-            state.current_screen = Screen::Settings;
+            state.navigate(Screen::Settings);
         }
         
         ui.add_space(15.0);
         
         if ui.add_sized(button_size, egui::Button::new("Leaderboard")).clicked() {
-            state.current_screen = Screen::Leaderboard;
+            state.navigate(Screen::Leaderboard);
+        }
+        
+        ui.add_space(15.0);
+        
+        if ui.add_sized(button_size, egui::Button::new("Stats")).clicked() {
+            state.navigate(Screen::Stats);
         }
         
         ui.add_space(15.0);
         
         if ui.add_sized(button_size, egui::Button::new("Profile")).clicked() {
-            state.current_screen = Screen::Profile;
+            state.navigate(Screen::Profile);
         }
         
         ui.add_space(15.0);
         
         if ui.add_sized(button_size, egui::Button::new("Share")).clicked() {
-            state.current_screen = Screen::Share;
+            state.navigate(Screen::Share);
         }
     });
-}
\ No newline at end of file
+
+    continue_game
+}
@@ -4,7 +4,11 @@ pub mod leaderboard;
 pub mod settings;
 pub mod profile;
 pub mod share;
+pub mod snake_skin;
+pub mod stats;
 pub mod components;
+pub mod dialogs;
+pub mod friends;
 
 use eframe::egui;
 
@@ -21,4 +25,18 @@ pub fn apply_modern_style(ctx: &egui::Context) {
     style.visuals.widgets.active.bg_fill = egui::Color32::from_rgb(0, 180, 0);
     
     ctx.set_style(style);
-}
\ No newline at end of file
+}
+
+/// Fades the freshly opened screen in from the panel colour.
+pub fn paint_screen_transition(ctx: &egui::Context, progress: f32) {
+    if progress >= 1.0 {
+        return;
+    }
+
+    let painter = ctx.layer_painter(egui::LayerId::new(
+        egui::Order::Foreground,
+        egui::Id::new("screen_transition"),
+    ));
+    let fill = ctx.style().visuals.panel_fill.gamma_multiply(1.0 - progress);
+    painter.rect_filled(ctx.screen_rect(), 0.0, fill);
+}
@@ -1,6 +1,10 @@
 use eframe::egui;
-use crate::resources::{GameState, Screen, UserProfile};
+use crate::resources::{GameMode, GameState, SnakeSkin, UserProfile};
+use crate::ui::snake_skin;
 use crate::constants::*;
+use crate::palette;
+use crate::theme::{self, Theme};
+#[cfg(target_os = "android")]
 use crate::ui::components;
 
 const TOP_SAFE_AREA: f32 = 24.0;
@@ -11,6 +15,7 @@ pub fn show_profile_screen(
     ui: &mut egui::Ui,
     state: &mut GameState,
     profile: &mut UserProfile,
+    theme: Option<&Theme>,
     show_keyboard: &mut bool,
 ) -> bool {
     let mut request_sync = false;
@@ -18,7 +23,7 @@ pub fn show_profile_screen(
 
     egui::ScrollArea::vertical().show(ui, |ui| {
         ui.vertical_centered(|ui| {
-            if draw_shared_profile_ui(ui, state, profile, Some(show_keyboard)) {
+            if draw_shared_profile_ui(ui, state, profile, theme, Some(show_keyboard)) {
                 request_sync = true;
             }
             ui.add_space(300.0);
@@ -37,12 +42,13 @@ pub fn show_profile_screen(
     ui: &mut egui::Ui,
     state: &mut GameState,
     profile: &mut UserProfile,
+    theme: Option<&Theme>,
 ) -> bool {
     ui.add_space(TOP_SAFE_AREA);
     
     let mut request_sync = false;
     ui.vertical_centered(|ui| {
-        if draw_shared_profile_ui(ui, state, profile, None) {
+        if draw_shared_profile_ui(ui, state, profile, theme, None) {
             request_sync = true;
         }
     });
@@ -53,13 +59,14 @@ fn draw_shared_profile_ui(
     ui: &mut egui::Ui,
     state: &mut GameState,
     profile: &mut UserProfile,
+    theme: Option<&Theme>,
     mut mobile_keyboard_trigger: Option<&mut bool>,
 ) -> bool {
     let mut changed = false;
 
     ui.horizontal(|ui| {
         if ui.add_sized([70.0, 35.0], egui::Button::new(egui::RichText::new("Back").size(12.5))).clicked() {
-            state.current_screen = Screen::MainMenu;
+            state.back();
             if let Some(trigger) = mobile_keyboard_trigger.as_deref_mut() {
                 *trigger = false; 
             }
@@ -100,6 +107,10 @@ fn draw_shared_profile_ui(
         ui.label(egui::RichText::new(format!("High Score: {}", profile.high_score))
             .size(18.0).color(egui::Color32::from_rgb(0, 255, 100)));
     });
+    for mode in GameMode::ALL {
+        ui.label(egui::RichText::new(format!("{}: {}", mode.label(), profile.best(mode)))
+            .size(14.0).color(egui::Color32::GRAY));
+    }
 
     ui.add_space(10.0);
     ui.label(egui::RichText::new(format!("ID: {}", &profile.user_id[..8.min(profile.user_id.len())]))
@@ -119,6 +130,58 @@ fn draw_shared_profile_ui(
             ui.add_space(12.0);
             
             if color_row(ui, "Apple", &mut profile.apple_color) { changed = true; }
+
+            // A theme can paint its own board background over the one picked here.
+            let background = theme::board_style(theme, profile).background;
+            let background = [background.r(), background.g(), background.b()];
+            let warnings = palette::contrast_warnings(profile.snake_color, profile.apple_color, background);
+            if !warnings.is_empty() {
+                ui.add_space(10.0);
+                for warning in warnings {
+                    ui.label(egui::RichText::new(format!("⚠ {}", warning))
+                        .size(12.0)
+                        .color(egui::Color32::from_rgb(255, 200, 0)));
+                }
+            }
+        });
+    });
+
+    ui.add_space(15.0);
+
+    ui.group(|ui| {
+        ui.set_width(ui.available_width());
+        ui.vertical(|ui| {
+            ui.label(egui::RichText::new("Palettes").size(14.0).color(egui::Color32::GRAY));
+            ui.add_space(5.0);
+            ui.horizontal_wrapped(|ui| {
+                for palette in &palette::PALETTES {
+                    if ui.button(egui::RichText::new(palette.name).size(14.0)).clicked() {
+                        profile.snake_color = palette.snake;
+                        profile.background_color = palette.background;
+                        profile.apple_color = palette.apple;
+                        changed = true;
+                    }
+                }
+            });
+        });
+    });
+
+    ui.add_space(15.0);
+
+    ui.group(|ui| {
+        ui.set_width(ui.available_width());
+        ui.vertical(|ui| {
+            ui.label(egui::RichText::new("Skin").size(14.0).color(egui::Color32::GRAY));
+            ui.add_space(5.0);
+            ui.horizontal_wrapped(|ui| {
+                for skin in SnakeSkin::ALL {
+                    if ui.selectable_value(&mut profile.skin, skin, egui::RichText::new(skin.label()).size(16.0)).changed() {
+                        changed = true;
+                    }
+                }
+            });
+            ui.add_space(10.0);
+            snake_skin::draw_preview(ui, profile.skin, profile.snake_color, 24.0);
         });
     });
 
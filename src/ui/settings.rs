@@ -1,10 +1,18 @@
 use eframe::egui;
-use crate::resources::{GameState, Screen};
+use crate::input::bindings::{self, Action, KeyBindings};
+use crate::constants::UI_SCALE_RANGE;
+use crate::theme::ThemeManager;
+use crate::resources::{ControlMode, GameState, Settings};
 
 const TOP_SAFE_AREA: f32 = 24.0;
 const BOTTOM_SAFE_AREA: f32 = 24.0;
 
-pub fn show_settings_screen(ui: &mut egui::Ui, state: &mut GameState) {
+pub fn show_settings_screen(
+    ui: &mut egui::Ui,
+    state: &mut GameState,
+    settings: &mut Settings,
+    themes: &mut ThemeManager,
+) {
     ui.add_space(TOP_SAFE_AREA);
     
     ui.vertical_centered(|ui| {
@@ -12,7 +20,8 @@ pub fn show_settings_screen(ui: &mut egui::Ui, state: &mut GameState) {
             if ui.add_sized([70.0, 35.0], egui::Button::new(
                 egui::RichText::new("Back").size(12.5)
             )).clicked() {
-                state.current_screen = Screen::MainMenu;
+                state.back();
+                bindings::set_capture_target(ui.ctx(), None);
             }
         });
         
@@ -20,26 +29,231 @@ pub fn show_settings_screen(ui: &mut egui::Ui, state: &mut GameState) {
         
         ui.heading(egui::RichText::new("Settings").size(28.0));
         
-        ui.add_space(80.0);
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            ui.add_space(30.0);
+
+            ui.group(|ui| {
+                ui.set_width(ui.available_width());
+                ui.vertical(|ui| {
+                    ui.label(egui::RichText::new("Controls").size(14.0).color(egui::Color32::GRAY));
+                    ui.add_space(5.0);
+
+                    let controls = &mut settings.controls;
+                    ui.horizontal(|ui| {
+                        for mode in ControlMode::ALL {
+                            ui.selectable_value(
+                                &mut controls.mode,
+                                mode,
+                                egui::RichText::new(mode.label()).size(16.0),
+                            );
+                        }
+                    });
+
+                    if controls.mode.uses_relative() {
+                        ui.add_space(5.0);
+                        ui.label(egui::RichText::new("Tap the left or right side to turn. Q / E on a keyboard.")
+                            .size(12.0)
+                            .color(egui::Color32::GRAY));
+                    }
+
+                    if controls.mode.uses_swipe() {
+                        ui.add_space(8.0);
+                        let swipe = &mut controls.swipe;
+                        ui.horizontal(|ui| {
+                            ui.label(egui::RichText::new("Swipe distance").size(14.0));
+                            ui.add(egui::Slider::new(&mut swipe.min_distance, 10.0..=150.0).suffix(" pt"));
+                        });
+                        ui.horizontal(|ui| {
+                            ui.label(egui::RichText::new("Swipe speed").size(14.0));
+                            ui.add(egui::Slider::new(&mut swipe.min_velocity, 0.0..=2000.0).suffix(" pt/s"));
+                        });
+                        ui.horizontal(|ui| {
+                            ui.label(egui::RichText::new("Diagonal dead zone").size(14.0));
+                            ui.add(egui::Slider::new(&mut swipe.dead_zone_degrees, 0.0..=30.0).suffix("°"));
+                        });
+                    }
+                });
+            });
+
+            ui.add_space(20.0);
+
+            ui.group(|ui| {
+                ui.set_width(ui.available_width());
+                ui.vertical(|ui| {
+                    draw_key_bindings(ui, &mut settings.key_bindings);
+                });
+            });
+
+            ui.add_space(20.0);
+
+            ui.group(|ui| {
+                ui.set_width(ui.available_width());
+                ui.vertical(|ui| {
+                    ui.label(egui::RichText::new("Sound").size(14.0).color(egui::Color32::GRAY));
+                    ui.add_space(5.0);
+
+                    let audio = &mut settings.audio;
+                    ui.checkbox(&mut audio.muted, egui::RichText::new("Mute all").size(16.0));
+                    ui.add_space(5.0);
+
+                    ui.add_enabled_ui(!audio.muted, |ui| {
+                        ui.horizontal(|ui| {
+                            ui.label(egui::RichText::new("Effects").size(16.0));
+                            ui.add(egui::Slider::new(&mut audio.sfx_volume, 0.0..=1.0).show_value(false));
+                        });
+                        ui.add_space(5.0);
+                        ui.checkbox(&mut audio.music_enabled, egui::RichText::new("Music").size(16.0));
+                        ui.add_enabled_ui(audio.music_enabled, |ui| {
+                            ui.horizontal(|ui| {
+                                ui.label(egui::RichText::new("Music volume").size(16.0));
+                                ui.add(egui::Slider::new(&mut audio.music_volume, 0.0..=1.0).show_value(false));
+                            });
+                        });
+                    });
+                });
+            });
+
+            ui.add_space(20.0);
+
+            ui.group(|ui| {
+                ui.set_width(ui.available_width());
+                ui.vertical(|ui| {
+                    ui.label(egui::RichText::new("Display").size(14.0).color(egui::Color32::GRAY));
+                    ui.add_space(5.0);
+                    ui.checkbox(&mut settings.reduced_motion, egui::RichText::new("Reduced motion").size(16.0));
+                    ui.label(egui::RichText::new("Turns off particles, screen shake and pop-ups")
+                        .size(12.0)
+                        .color(egui::Color32::GRAY));
+                    ui.add_space(10.0);
+                    ui.checkbox(&mut settings.display.high_contrast, egui::RichText::new("High contrast").size(16.0));
+                    ui.label(egui::RichText::new("Adds outlines and draws the apple as a diamond")
+                        .size(12.0)
+                        .color(egui::Color32::GRAY));
+                    ui.add_space(10.0);
+                    ui.add(egui::Slider::new(&mut settings.display.ui_scale, UI_SCALE_RANGE)
+                        .step_by(0.05)
+                        .text("UI scale"));
+                });
+            });
+
+            ui.add_space(20.0);
+
+            ui.group(|ui| {
+                ui.set_width(ui.available_width());
+                ui.vertical(|ui| {
+                    ui.label(egui::RichText::new("Theme").size(14.0).color(egui::Color32::GRAY));
+                    ui.add_space(5.0);
+                    draw_theme_picker(ui, settings, themes);
+                });
+            });
+
+            ui.add_space(40.0);
         
-        ui.label(egui::RichText::new("Coming Soon")
-            .size(28.0)
-            .color(egui::Color32::from_rgb(255, 200, 0)));
+            ui.label(egui::RichText::new("Coming Soon")
+                .size(28.0)
+                .color(egui::Color32::from_rgb(255, 200, 0)));
         
-        ui.add_space(40.0);
+            ui.add_space(40.0);
         
-        ui.label(egui::RichText::new("Future features:")
-            .size(16.0)
-            .color(egui::Color32::GRAY));
-        ui.add_space(15.0);
-        ui.label(egui::RichText::new("• Sound effects").size(16.0));
-        ui.add_space(8.0);
-        ui.label(egui::RichText::new("• Game speed settings").size(16.0));
-        ui.add_space(8.0);
-        ui.label(egui::RichText::new("• Snake skins").size(16.0));
-        ui.add_space(8.0);
-        ui.label(egui::RichText::new("• Difficulty modes").size(16.0));
+            ui.label(egui::RichText::new("Future features:")
+                .size(16.0)
+                .color(egui::Color32::GRAY));
+            ui.add_space(15.0);
+            ui.label(egui::RichText::new("• Game speed settings").size(16.0));
+            ui.add_space(8.0);
+            ui.label(egui::RichText::new("• Difficulty modes").size(16.0));
         
-        ui.add_space(BOTTOM_SAFE_AREA);
+            ui.add_space(BOTTOM_SAFE_AREA);
+        });
     });
-}
\ No newline at end of file
+}
+
+fn draw_key_bindings(ui: &mut egui::Ui, key_bindings: &mut KeyBindings) {
+    let ctx = ui.ctx().clone();
+    let conflict_id = egui::Id::new("key_binding_conflict");
+    let capturing = bindings::capture_target(&ctx);
+
+    if let Some(action) = capturing {
+        if let Some(key) = bindings::next_key_press(&ctx) {
+            match key_bindings.bind(action, key) {
+                Ok(()) => ctx.data_mut(|d| d.remove::<String>(conflict_id)),
+                Err(other) => {
+                    let message = format!("{} is already bound to {}", key.name(), other.label());
+                    ctx.data_mut(|d| d.insert_temp(conflict_id, message));
+                }
+            }
+            bindings::set_capture_target(&ctx, None);
+        }
+    }
+
+    ui.label(egui::RichText::new("Key Bindings").size(14.0).color(egui::Color32::GRAY));
+    ui.add_space(5.0);
+
+    egui::Grid::new("key_bindings_grid")
+        .num_columns(2)
+        .spacing([12.0, 6.0])
+        .show(ui, |ui| {
+            for action in Action::ALL {
+                ui.label(egui::RichText::new(action.label()).size(14.0));
+                ui.horizontal_wrapped(|ui| {
+                    for key in key_bindings.keys(action) {
+                        if ui.small_button(format!("{} ×", key.name()))
+                            .on_hover_text("Remove")
+                            .clicked()
+                        {
+                            key_bindings.unbind(action, key);
+                        }
+                    }
+
+                    if capturing == Some(action) {
+                        ui.label(egui::RichText::new("Press a key…").color(egui::Color32::from_rgb(255, 200, 0)));
+                        if ui.small_button("Cancel").clicked() {
+                            bindings::set_capture_target(&ctx, None);
+                        }
+                    } else if ui.small_button("+").on_hover_text("Add key").clicked() {
+                        bindings::set_capture_target(&ctx, Some(action));
+                        ctx.data_mut(|d| d.remove::<String>(conflict_id));
+                    }
+                });
+                ui.end_row();
+            }
+        });
+
+    if let Some(message) = ctx.data(|d| d.get_temp::<String>(conflict_id)) {
+        ui.add_space(5.0);
+        ui.colored_label(egui::Color32::from_rgb(255, 100, 100), message);
+    }
+
+    ui.add_space(8.0);
+    if ui.button("Reset to defaults").clicked() {
+        *key_bindings = KeyBindings::default();
+        bindings::set_capture_target(&ctx, None);
+        ctx.data_mut(|d| d.remove::<String>(conflict_id));
+    }
+}
+
+fn draw_theme_picker(ui: &mut egui::Ui, settings: &mut Settings, themes: &mut ThemeManager) {
+    let selected = &mut settings.display.theme;
+
+    ui.horizontal_wrapped(|ui| {
+        ui.selectable_value(selected, None, egui::RichText::new("Default").size(16.0));
+        for info in &themes.available {
+            ui.selectable_value(selected, Some(info.id.clone()), egui::RichText::new(&info.name).size(16.0));
+        }
+    });
+
+    if let Some(error) = &themes.error {
+        ui.add_space(5.0);
+        ui.label(egui::RichText::new(error).size(12.0).color(egui::Color32::from_rgb(255, 100, 100)));
+    }
+
+    ui.add_space(5.0);
+    if let Some(dir) = themes.themes_dir() {
+        ui.label(egui::RichText::new(format!("Add packs to {}", dir.display()))
+            .size(12.0)
+            .color(egui::Color32::GRAY));
+    }
+    if ui.button("Rescan").clicked() {
+        themes.rescan();
+    }
+}
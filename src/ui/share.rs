@@ -1,35 +1,28 @@
 use eframe::egui;
 use qrcode::{QrCode, Color as QrColor};
-use crate::resources::{GameState, Screen, QRCodeTextures};
+use crate::resources::{GameState, QRCodeTextures};
 use crate::constants::{ANDROID_DOWNLOAD_URL, IOS_DOWNLOAD_URL};
 
 const TOP_SAFE_AREA: f32 = 24.0;
 const BOTTOM_SAFE_AREA: f32 = 24.0;
 
-pub fn generate_qr_textures(ctx: &egui::Context) -> QRCodeTextures {
-    let mut textures = QRCodeTextures::default();
-
-    let create_qr = |url: &str, name: &str| -> egui::TextureHandle {
-        let code = QrCode::new(url.as_bytes()).unwrap();
-        let image = egui::ColorImage {
-            size: [code.width(), code.width()],
-            pixels: code.to_colors()
-                .into_iter()
-                .map(|c| if c == QrColor::Dark { egui::Color32::BLACK } else { egui::Color32::WHITE })
-                .collect(),
-        };
-        ctx.load_texture(name, image, egui::TextureOptions::NEAREST)
+pub fn qr_texture(ctx: &egui::Context, data: &str, name: &str) -> egui::TextureHandle {
+    let code = QrCode::new(data.as_bytes()).unwrap();
+    let image = egui::ColorImage {
+        size: [code.width(), code.width()],
+        pixels: code.to_colors()
+            .into_iter()
+            .map(|c| if c == QrColor::Dark { egui::Color32::BLACK } else { egui::Color32::WHITE })
+            .collect(),
     };
-
-    textures.android_qr = Some(create_qr(ANDROID_DOWNLOAD_URL, "android_qr"));
-    textures.ios_qr = Some(create_qr(IOS_DOWNLOAD_URL, "ios_qr"));
-
-    textures
+    ctx.load_texture(name, image, egui::TextureOptions::NEAREST)
 }
 
+
 pub fn show_share_screen(ui: &mut egui::Ui, state: &mut GameState, qr_textures: &mut QRCodeTextures, ctx: &egui::Context) {
     if qr_textures.android_qr.is_none() {
-        *qr_textures = generate_qr_textures(ctx);
+        qr_textures.android_qr = Some(qr_texture(ctx, ANDROID_DOWNLOAD_URL, "android_qr"));
+        qr_textures.ios_qr = Some(qr_texture(ctx, IOS_DOWNLOAD_URL, "ios_qr"));
     }
 
     ui.add_space(TOP_SAFE_AREA);
@@ -39,7 +32,7 @@ pub fn show_share_screen(ui: &mut egui::Ui, state: &mut GameState, qr_textures:
             if ui.add_sized([70.0, 35.0], egui::Button::new(
                 egui::RichText::new("Back").size(12.5)
             )).clicked() {
-                state.current_screen = Screen::MainMenu;
+                state.back();
             }
         });
         
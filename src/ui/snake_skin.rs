@@ -0,0 +1,353 @@
+use eframe::egui;
+use crate::resources::{Direction, SnakeSkin};
+
+const SPRITE_SIZE: usize = 8;
+
+// Pixel-art tiles, all authored facing up. `B` is the snake colour, `L` and `D`
+// lighter and darker shades, `W` and `K` the eye white and pupil.
+const HEAD_TILE: [&str; SPRITE_SIZE] = [
+    "..BBBB..",
+    ".BBBBBB.",
+    ".WKBBKW.",
+    ".WWBBWW.",
+    ".BBBBBB.",
+    ".LBBBBD.",
+    ".LBBBBD.",
+    ".LBBBBD.",
+];
+
+const BODY_TILE: [&str; SPRITE_SIZE] = [
+    ".LBBBBD.",
+    ".LBDDBD.",
+    ".LBBBBD.",
+    ".LBBBBD.",
+    ".LBDDBD.",
+    ".LBBBBD.",
+    ".LBBBBD.",
+    ".LBDDBD.",
+];
+
+/// Joins the top edge to the right edge.
+const CORNER_TILE: [&str; SPRITE_SIZE] = [
+    ".LBBBBD.",
+    ".LBBBBBB",
+    ".LBBBBBB",
+    ".LBBDBBB",
+    ".LBBBBBB",
+    ".LBBBBBB",
+    "..DDDDDD",
+    "........",
+];
+
+const TAIL_TILE: [&str; SPRITE_SIZE] = [
+    ".LBBBBD.",
+    ".LBDDBD.",
+    ".LBBBBD.",
+    "..LBBD..",
+    "..LBBD..",
+    "...BD...",
+    "...BD...",
+    "........",
+];
+
+const SHEET: [[&str; SPRITE_SIZE]; 4] = [HEAD_TILE, BODY_TILE, CORNER_TILE, TAIL_TILE];
+
+#[derive(Clone, Copy)]
+enum Tile {
+    Head = 0,
+    Body = 1,
+    Corner = 2,
+    Tail = 3,
+}
+
+/// Segments from head to tail, plus the heading used when there is no neck to infer it from.
+pub struct SnakeLayout<'a> {
+    pub snake: &'a [(i32, i32)],
+    pub heading: Direction,
+}
+
+pub fn draw_snake(
+    painter: &egui::Painter,
+    origin: egui::Pos2,
+    cell_size: f32,
+    layout: &SnakeLayout,
+    skin: SnakeSkin,
+    color: [u8; 3],
+) {
+    let sheet_id = egui::Id::new("snake_pixel_sheet");
+    draw_snake_with_sheet(painter, sheet_id, origin, cell_size, layout, skin, color);
+}
+
+/// `sheet_id` names the slot the pixel-art sheet is cached in, so snakes drawn
+/// side by side in different colours don't keep repainting each other's sheet.
+fn draw_snake_with_sheet(
+    painter: &egui::Painter,
+    sheet_id: egui::Id,
+    origin: egui::Pos2,
+    cell_size: f32,
+    layout: &SnakeLayout,
+    skin: SnakeSkin,
+    color: [u8; 3],
+) {
+    let base = egui::Color32::from_rgb(color[0], color[1], color[2]);
+    let cell_rect = |(x, y): (i32, i32)| {
+        egui::Rect::from_min_size(
+            origin + egui::vec2(x as f32, y as f32) * cell_size,
+            egui::vec2(cell_size, cell_size),
+        )
+    };
+
+    match skin {
+        SnakeSkin::Classic => {
+            let inset = painter.round_to_pixel(cell_size * 0.1).max(1.0);
+            for (i, &segment) in layout.snake.iter().enumerate() {
+                let fill = if i == 0 { lighten(base, 55) } else { base };
+                painter.rect_filled(cell_rect(segment).shrink(inset), cell_size * 0.2, fill);
+            }
+        }
+        SnakeSkin::Rounded => {
+            let width = cell_size * 0.8;
+            for pair in layout.snake.windows(2) {
+                if is_adjacent(pair[0], pair[1]) {
+                    painter.line_segment(
+                        [cell_rect(pair[0]).center(), cell_rect(pair[1]).center()],
+                        egui::Stroke::new(width, base),
+                    );
+                }
+            }
+            for &segment in layout.snake {
+                painter.circle_filled(cell_rect(segment).center(), width / 2.0, base);
+            }
+        }
+        SnakeSkin::Gradient => {
+            let last = layout.snake.len().saturating_sub(1).max(1) as f32;
+            for (i, &segment) in layout.snake.iter().enumerate().rev() {
+                let fill = darken(base, 1.0 - 0.65 * i as f32 / last);
+                painter.rect_filled(cell_rect(segment).shrink(cell_size * 0.05), cell_size * 0.3, fill);
+            }
+        }
+        SnakeSkin::Striped => {
+            let stripe = darken(base, 0.55);
+            for (i, &segment) in layout.snake.iter().enumerate() {
+                let fill = if (i / 2) % 2 == 0 { base } else { stripe };
+                painter.rect_filled(cell_rect(segment).shrink(cell_size * 0.05), cell_size * 0.15, fill);
+            }
+        }
+        SnakeSkin::PixelArt => {
+            let texture = pixel_sheet(painter.ctx(), sheet_id, color);
+            draw_snake_sprites(painter, origin, cell_size, layout, &texture);
+            return;
+        }
+    }
+
+    if let Some(&head) = layout.snake.first() {
+        draw_eyes(painter, cell_rect(head), head_direction(layout), cell_size);
+    }
+}
+
+/// Draws the snake from a sheet laid out like the built-in pixel-art one:
+/// head, straight body, corner and tail tiles side by side, all facing up.
+pub fn draw_snake_sprites(
+    painter: &egui::Painter,
+    origin: egui::Pos2,
+    cell_size: f32,
+    layout: &SnakeLayout,
+    texture: &egui::TextureHandle,
+) {
+    for (i, &(x, y)) in layout.snake.iter().enumerate() {
+        let rect = egui::Rect::from_min_size(
+            origin + egui::vec2(x as f32, y as f32) * cell_size,
+            egui::vec2(cell_size, cell_size),
+        );
+        let (tile, turns) = pixel_tile(layout, i);
+        draw_tile(painter, texture, rect, tile, turns);
+    }
+}
+
+fn draw_eyes(painter: &egui::Painter, head: egui::Rect, heading: Direction, cell_size: f32) {
+    let forward = direction_vec(heading);
+    let side = egui::vec2(-forward.y, forward.x);
+    let center = head.center() + forward * cell_size * 0.15;
+
+    for offset in [side, -side] {
+        let eye = center + offset * cell_size * 0.2;
+        painter.circle_filled(eye, cell_size * 0.13, egui::Color32::WHITE);
+        painter.circle_filled(eye + forward * cell_size * 0.05, cell_size * 0.065, egui::Color32::BLACK);
+    }
+}
+
+/// Picks the sprite for segment `index` and how many clockwise quarter turns it needs.
+fn pixel_tile(layout: &SnakeLayout, index: usize) -> (Tile, usize) {
+    let snake = layout.snake;
+    let segment = snake[index];
+
+    if index == 0 {
+        return (Tile::Head, quarter_turns(head_direction(layout)));
+    }
+
+    let toward_head = direction_between(segment, snake[index - 1]);
+    let Some(&behind) = snake.get(index + 1) else {
+        return (Tile::Tail, quarter_turns(toward_head));
+    };
+    let toward_tail = direction_between(segment, behind);
+
+    if toward_head == toward_tail.opposite() {
+        let vertical = matches!(toward_head, Direction::Up | Direction::Down);
+        return (Tile::Body, if vertical { 0 } else { 1 });
+    }
+
+    // The corner tile joins Up and Right; find the rotation that maps it onto this bend.
+    let turns = (0..4)
+        .find(|&turns| {
+            let a = rotate(Direction::Up, turns);
+            let b = rotate(Direction::Right, turns);
+            (a == toward_head && b == toward_tail) || (a == toward_tail && b == toward_head)
+        })
+        .unwrap_or(0);
+    (Tile::Corner, turns)
+}
+
+fn draw_tile(painter: &egui::Painter, texture: &egui::TextureHandle, rect: egui::Rect, tile: Tile, turns: usize) {
+    let count = SHEET.len() as f32;
+    let u0 = tile as usize as f32 / count;
+    let u1 = (tile as usize + 1) as f32 / count;
+    let uvs = [egui::pos2(u0, 0.0), egui::pos2(u1, 0.0), egui::pos2(u1, 1.0), egui::pos2(u0, 1.0)];
+    let corners = [rect.left_top(), rect.right_top(), rect.right_bottom(), rect.left_bottom()];
+
+    let mut mesh = egui::Mesh::with_texture(texture.id());
+    for (i, corner) in corners.into_iter().enumerate() {
+        mesh.vertices.push(egui::epaint::Vertex {
+            pos: corner,
+            uv: uvs[(i + 4 - turns % 4) % 4],
+            color: egui::Color32::WHITE,
+        });
+    }
+    mesh.add_triangle(0, 1, 2);
+    mesh.add_triangle(0, 2, 3);
+    painter.add(egui::Shape::mesh(mesh));
+}
+
+/// The sprite sheet is tinted with the snake colour. Each slot keeps only the
+/// sheet for its current colour; changing colour repaints the same texture.
+fn pixel_sheet(ctx: &egui::Context, id: egui::Id, color: [u8; 3]) -> egui::TextureHandle {
+    let cached = ctx.data(|d| d.get_temp::<([u8; 3], egui::TextureHandle)>(id));
+    if let Some((cached_color, texture)) = &cached {
+        if *cached_color == color {
+            return texture.clone();
+        }
+    }
+
+    let base = egui::Color32::from_rgb(color[0], color[1], color[2]);
+    let width = SPRITE_SIZE * SHEET.len();
+    let mut pixels = vec![egui::Color32::TRANSPARENT; width * SPRITE_SIZE];
+
+    for (t, tile) in SHEET.iter().enumerate() {
+        for (y, row) in tile.iter().enumerate() {
+            for (x, c) in row.chars().enumerate() {
+                pixels[y * width + t * SPRITE_SIZE + x] = match c {
+                    'B' => base,
+                    'L' => lighten(base, 50),
+                    'D' => darken(base, 0.6),
+                    'W' => egui::Color32::WHITE,
+                    'K' => egui::Color32::BLACK,
+                    _ => egui::Color32::TRANSPARENT,
+                };
+            }
+        }
+    }
+
+    let image = egui::ColorImage { size: [width, SPRITE_SIZE], pixels };
+    let texture = match cached {
+        Some((_, mut texture)) => {
+            texture.set(image, egui::TextureOptions::NEAREST);
+            texture
+        }
+        None => ctx.load_texture("snake_pixel_sheet", image, egui::TextureOptions::NEAREST),
+    };
+    ctx.data_mut(|d| d.insert_temp(id, (color, texture.clone())));
+    texture
+}
+
+fn head_direction(layout: &SnakeLayout) -> Direction {
+    match layout.snake {
+        [head, neck, ..] => direction_between(*neck, *head),
+        _ => layout.heading,
+    }
+}
+
+/// Direction of the step from `from` to the neighbouring cell `to`. A jump of
+/// more than one cell means the snake wrapped around the edge of the board.
+fn direction_between(from: (i32, i32), to: (i32, i32)) -> Direction {
+    let mut dx = to.0 - from.0;
+    let mut dy = to.1 - from.1;
+    if dx.abs() > 1 {
+        dx = -dx.signum();
+    }
+    if dy.abs() > 1 {
+        dy = -dy.signum();
+    }
+    match (dx, dy) {
+        (1, _) => Direction::Right,
+        (-1, _) => Direction::Left,
+        (_, 1) => Direction::Down,
+        _ => Direction::Up,
+    }
+}
+
+fn is_adjacent(a: (i32, i32), b: (i32, i32)) -> bool {
+    (a.0 - b.0).abs() + (a.1 - b.1).abs() == 1
+}
+
+fn quarter_turns(direction: Direction) -> usize {
+    match direction {
+        Direction::Up => 0,
+        Direction::Right => 1,
+        Direction::Down => 2,
+        Direction::Left => 3,
+    }
+}
+
+fn rotate(direction: Direction, turns: usize) -> Direction {
+    (0..turns).fold(direction, |d, _| match d {
+        Direction::Up => Direction::Right,
+        Direction::Right => Direction::Down,
+        Direction::Down => Direction::Left,
+        Direction::Left => Direction::Up,
+    })
+}
+
+fn direction_vec(direction: Direction) -> egui::Vec2 {
+    match direction {
+        Direction::Up => egui::vec2(0.0, -1.0),
+        Direction::Down => egui::vec2(0.0, 1.0),
+        Direction::Left => egui::vec2(-1.0, 0.0),
+        Direction::Right => egui::vec2(1.0, 0.0),
+    }
+}
+
+fn lighten(color: egui::Color32, amount: u8) -> egui::Color32 {
+    egui::Color32::from_rgb(
+        color.r().saturating_add(amount),
+        color.g().saturating_add(amount),
+        color.b().saturating_add(amount),
+    )
+}
+
+fn darken(color: egui::Color32, factor: f32) -> egui::Color32 {
+    let scale = |c: u8| (c as f32 * factor) as u8;
+    egui::Color32::from_rgb(scale(color.r()), scale(color.g()), scale(color.b()))
+}
+
+/// A short straight snake facing right, for skin pickers and leaderboard rows.
+pub fn draw_preview(ui: &mut egui::Ui, skin: SnakeSkin, color: [u8; 3], cell_size: f32) -> egui::Response {
+    const PREVIEW: [(i32, i32); 4] = [(3, 0), (2, 0), (1, 0), (0, 0)];
+
+    let (response, painter) = ui.allocate_painter(
+        egui::vec2(PREVIEW.len() as f32 * cell_size, cell_size),
+        egui::Sense::hover(),
+    );
+    let layout = SnakeLayout { snake: &PREVIEW, heading: Direction::Right };
+    let origin = painter.round_pos_to_pixels(response.rect.min);
+    draw_snake_with_sheet(&painter, response.id.with("pixel_sheet"), origin, cell_size, &layout, skin, color);
+    response
+}
@@ -0,0 +1,123 @@
+use eframe::egui;
+use crate::resources::GameState;
+use crate::stats::{format_play_time, LifetimeStats};
+
+const TOP_SAFE_AREA: f32 = 24.0;
+const BOTTOM_SAFE_AREA: f32 = 24.0;
+const CHART_HEIGHT: f32 = 140.0;
+
+pub fn show_stats_screen(ui: &mut egui::Ui, state: &mut GameState, stats: &LifetimeStats) {
+    ui.add_space(TOP_SAFE_AREA);
+
+    ui.vertical_centered(|ui| {
+        ui.horizontal(|ui| {
+            if ui.add_sized([70.0, 35.0], egui::Button::new(
+                egui::RichText::new("Back").size(12.5)
+            )).clicked() {
+                state.back();
+            }
+        });
+
+        ui.add_space(20.0);
+        ui.heading(egui::RichText::new("Statistics").size(28.0));
+        ui.add_space(20.0);
+
+        if stats.games_played == 0 {
+            ui.label(egui::RichText::new("No games played yet").size(18.0).color(egui::Color32::GRAY));
+            ui.add_space(15.0);
+            ui.label(egui::RichText::new("Finish a game to start tracking stats!").size(16.0));
+            return;
+        }
+
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            ui.group(|ui| {
+                ui.set_width(ui.available_width());
+                ui.vertical(|ui| {
+                    stat_row(ui, "Games played", stats.games_played.to_string());
+                    stat_row(ui, "Average score", format!("{:.1}", stats.average_score()));
+                    stat_row(ui, "Apples eaten", stats.total_score.to_string());
+                    stat_row(ui, "Longest snake", stats.longest_snake.to_string());
+                    stat_row(ui, "Time played", format_play_time(stats.total_play_time));
+                });
+            });
+
+            ui.add_space(25.0);
+            section_heading(ui, "Recent Scores");
+            let scores: Vec<u32> = stats.recent_games.iter().map(|g| g.score).collect();
+            draw_score_history(ui, &scores);
+
+
+            ui.add_space(BOTTOM_SAFE_AREA);
+        });
+    });
+}
+
+fn section_heading(ui: &mut egui::Ui, text: &str) {
+    ui.label(egui::RichText::new(text).size(18.0).strong());
+    ui.add_space(10.0);
+}
+
+fn stat_row(ui: &mut egui::Ui, label: &str, value: String) {
+    ui.horizontal(|ui| {
+        ui.label(egui::RichText::new(label).size(16.0).color(egui::Color32::GRAY));
+        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+            ui.label(egui::RichText::new(value)
+                .size(16.0)
+                .color(egui::Color32::from_rgb(0, 255, 100)));
+        });
+    });
+    ui.add_space(6.0);
+}
+
+fn draw_score_history(ui: &mut egui::Ui, scores: &[u32]) {
+    let (response, painter) = ui.allocate_painter(
+        egui::vec2(ui.available_width(), CHART_HEIGHT),
+        egui::Sense::hover(),
+    );
+    let rect = response.rect;
+
+    painter.rect_filled(rect, 6.0, egui::Color32::from_gray(25));
+
+    let max_score = scores.iter().copied().max().unwrap_or(0).max(1) as f32;
+    let plot = rect.shrink(12.0);
+
+    painter.line_segment(
+        [plot.left_bottom(), plot.right_bottom()],
+        egui::Stroke::new(1.0, egui::Color32::from_gray(80)),
+    );
+    painter.text(
+        plot.left_top(),
+        egui::Align2::LEFT_TOP,
+        format!("{}", max_score as u32),
+        egui::FontId::proportional(11.0),
+        egui::Color32::GRAY,
+    );
+
+    if scores.len() < 2 {
+        if let Some(&score) = scores.first() {
+            let y = plot.bottom() - plot.height() * (score as f32 / max_score);
+            painter.circle_filled(egui::pos2(plot.center().x, y), 3.5, egui::Color32::from_rgb(0, 255, 100));
+        }
+        return;
+    }
+
+    let step = plot.width() / (scores.len() - 1) as f32;
+    let points: Vec<egui::Pos2> = scores
+        .iter()
+        .enumerate()
+        .map(|(i, &score)| {
+            egui::pos2(
+                plot.left() + i as f32 * step,
+                plot.bottom() - plot.height() * (score as f32 / max_score),
+            )
+        })
+        .collect();
+
+    painter.add(egui::Shape::line(
+        points.clone(),
+        egui::Stroke::new(2.0, egui::Color32::from_rgb(0, 200, 0)),
+    ));
+    for point in points {
+        painter.circle_filled(point, 2.5, egui::Color32::from_rgb(0, 255, 100));
+    }
+}